@@ -0,0 +1,94 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::{ArchiveType, Platform, ReleaseType};
+use semver::Version;
+use std::fmt;
+
+/// A release archive, identified by the parts of its file name, e.g.,
+/// `antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ReleaseArtifact {
+    pub release_type: ReleaseType,
+    pub version: Version,
+    pub platform: Platform,
+    pub archive_type: ArchiveType,
+}
+
+impl ReleaseArtifact {
+    pub fn new(
+        release_type: ReleaseType,
+        version: Version,
+        platform: Platform,
+        archive_type: ArchiveType,
+    ) -> Self {
+        Self {
+            release_type,
+            version,
+            platform,
+            archive_type,
+        }
+    }
+
+    /// Parses the file name of a release archive into its parts.
+    ///
+    /// The version may have pre-release and build metadata, e.g.,
+    /// `nat-detection-0.2.11-rc.1-aarch64-apple-darwin.zip`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidArchiveName` error if the name is not made up of a known release type,
+    /// a semantic version, a supported target triple and the extension of an `ArchiveType`.
+    pub fn parse(file_name: &str) -> Result<Self> {
+        let invalid = || Error::InvalidArchiveName(file_name.to_string());
+        let (stem, archive_type) = ArchiveType::all()
+            .find_map(|archive_type| {
+                let stem = file_name.strip_suffix(&format!(".{archive_type}"))?;
+                Some((stem, archive_type))
+            })
+            .ok_or_else(invalid)?;
+
+        // Both the release type and the version can contain dashes, so try each way of splitting
+        // the name until the parts are all valid.
+        for (i, _) in stem.match_indices('-') {
+            let Some(release_type) = ReleaseType::from_binary_name(&stem[..i]) else {
+                continue;
+            };
+            let rest = &stem[i + 1..];
+            for (j, _) in rest.match_indices('-') {
+                if let (Ok(version), Ok(platform)) = (
+                    Version::parse(&rest[..j]),
+                    rest[j + 1..].parse::<Platform>(),
+                ) {
+                    return Ok(Self::new(release_type, version, platform, archive_type));
+                }
+            }
+        }
+        Err(invalid())
+    }
+
+    /// Returns the file name the archive is published under.
+    pub fn to_file_name(&self) -> String {
+        format!(
+            "{}-{}-{}.{}",
+            self.release_type, self.version, self.platform, self.archive_type
+        )
+    }
+
+    /// Returns the URL of the archive in the bucket or directory at `base_url`.
+    pub fn to_url(&self, base_url: &str) -> String {
+        format!("{}/{}", base_url.trim_end_matches('/'), self.to_file_name())
+    }
+}
+
+impl fmt::Display for ReleaseArtifact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_file_name())
+    }
+}
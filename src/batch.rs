@@ -0,0 +1,79 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Result;
+use crate::{ArchiveType, Platform, ProgressEvent, ReleaseType};
+use semver::{Version, VersionReq};
+use std::path::PathBuf;
+
+/// The number of downloads in a batch that run at the same time, unless configured otherwise.
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// Receives the progress of each download in a batch, along with the index of its target.
+pub type BatchProgressCallback = dyn Fn(usize, ProgressEvent) + Send + Sync;
+
+/// Which version of a release to download as part of a batch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum VersionSelector {
+    Exact(Version),
+    /// The highest version that matches the requirement and hasn't been yanked. Pre-releases are
+    /// only selected if the requirement names one.
+    Requirement(VersionReq),
+}
+
+impl From<Version> for VersionSelector {
+    fn from(version: Version) -> Self {
+        VersionSelector::Exact(version)
+    }
+}
+
+impl From<VersionReq> for VersionSelector {
+    fn from(version_req: VersionReq) -> Self {
+        VersionSelector::Requirement(version_req)
+    }
+}
+
+/// A release to download as part of a batch.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DownloadTarget {
+    pub release_type: ReleaseType,
+    pub version: VersionSelector,
+    pub platform: Platform,
+    pub archive_type: ArchiveType,
+}
+
+impl DownloadTarget {
+    /// Creates a target for the archive type releases are published in for the platform, i.e., a
+    /// zip archive for Windows and a gzipped tarball for everything else.
+    pub fn new(
+        release_type: ReleaseType,
+        version: impl Into<VersionSelector>,
+        platform: Platform,
+    ) -> Self {
+        let archive_type = match platform {
+            Platform::Windows => ArchiveType::Zip,
+            _ => ArchiveType::TarGz,
+        };
+        Self {
+            release_type,
+            version: version.into(),
+            platform,
+            archive_type,
+        }
+    }
+}
+
+/// The outcome of downloading one of the targets in a batch.
+#[derive(Debug)]
+pub struct BatchDownloadResult {
+    pub target: DownloadTarget,
+    /// The version the target was resolved to, or `None` if it couldn't be resolved.
+    pub version: Option<Version>,
+    /// The path of the downloaded archive, or the error that prevented it being downloaded.
+    pub result: Result<PathBuf>,
+}
@@ -0,0 +1,172 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Result;
+use crate::{
+    AntReleaseRepository, BinaryPermissions, ChecksumVerification, HttpConfig, ReleaseCache,
+    ReleaseType, RetryPolicy, SignatureVerification, VersionSource,
+};
+use std::time::Duration;
+
+/// Builds an `AntReleaseRepository`, starting from the defaults and overriding only what's set.
+///
+/// Created with `AntReleaseRepository::builder`.
+pub struct AntReleaseRepositoryBuilder {
+    repository: AntReleaseRepository,
+    http_config: Option<HttpConfig>,
+}
+
+impl AntReleaseRepositoryBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            repository: AntReleaseRepository::default(),
+            http_config: None,
+        }
+    }
+
+    /// Sets the URL archives of the release type are downloaded from.
+    ///
+    /// `antctl` and `antctld` are published to the same bucket, so setting the URL for either of
+    /// them applies to both.
+    pub fn base_url(mut self, release_type: ReleaseType, url: impl Into<String>) -> Self {
+        let url = url.into();
+        let repository = &mut self.repository;
+        match release_type {
+            ReleaseType::Ant => repository.ant_base_url = url,
+            ReleaseType::AntCtl | ReleaseType::AntCtlDaemon => repository.antctl_base_url = url,
+            ReleaseType::AntNode => repository.antnode_base_url = url,
+            ReleaseType::AntNodeRpcClient => repository.antnode_rpc_client_base_url = url,
+            ReleaseType::NatDetection => repository.nat_detection_base_url = url,
+            ReleaseType::NodeLaunchpad => repository.node_launchpad_base_url = url,
+        }
+        self
+    }
+
+    /// Sets the URL archives of every release type are downloaded from, e.g., to use a single
+    /// mirror or a local server.
+    pub fn all_base_urls(self, url: impl Into<String>) -> Self {
+        let url = url.into();
+        ReleaseType::all().fold(self, |builder, release_type| {
+            builder.base_url(release_type, url.clone())
+        })
+    }
+
+    /// Sets the mirrors that downloads of the release type fall over to, in order, when its base
+    /// URL is unavailable.
+    ///
+    /// As with `base_url`, the mirrors of `antctl` and `antctld` are shared.
+    pub fn mirrors<I, S>(mut self, release_type: ReleaseType, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let urls: Vec<String> = urls.into_iter().map(Into::into).collect();
+        let mirrors = &mut self.repository.mirrors;
+        match release_type {
+            ReleaseType::AntCtl | ReleaseType::AntCtlDaemon => {
+                mirrors.insert(ReleaseType::AntCtl, urls.clone());
+                mirrors.insert(ReleaseType::AntCtlDaemon, urls);
+            }
+            release_type => {
+                mirrors.insert(release_type, urls);
+            }
+        }
+        self
+    }
+
+    /// Sets how long a mirror that failed is only tried as a last resort.
+    pub fn mirror_cool_down(mut self, cool_down: Duration) -> Self {
+        self.repository.mirror_cool_down = cool_down;
+        self
+    }
+
+    /// Sets the base URL of the crates.io API, which versions are looked up from by default.
+    pub fn crates_io_api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.repository.crates_io_api_base_url = url.into();
+        self
+    }
+
+    pub fn github_api_base_url(mut self, url: impl Into<String>) -> Self {
+        self.repository.github_api_base_url = url.into();
+        self
+    }
+
+    /// Sets the GitHub repository releases are published to, in `owner/name` form.
+    pub fn github_repository(mut self, repository: impl Into<String>) -> Self {
+        self.repository.github_repository = repository.into();
+        self
+    }
+
+    pub fn github_token(mut self, token: impl Into<String>) -> Self {
+        self.repository.github_token = Some(token.into());
+        self
+    }
+
+    /// Sets the URL the WinSW service wrapper is downloaded from.
+    pub fn winsw_url(mut self, url: impl Into<String>) -> Self {
+        self.repository.winsw_url = url.into();
+        self
+    }
+
+    /// Sets the settings the HTTP client is built with when `build` is called.
+    pub fn http_config(mut self, http_config: HttpConfig) -> Self {
+        self.http_config = Some(http_config);
+        self
+    }
+
+    pub fn cache(mut self, cache: ReleaseCache) -> Self {
+        self.repository.cache = Some(cache);
+        self
+    }
+
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.repository.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn checksum_verification(mut self, checksum_verification: ChecksumVerification) -> Self {
+        self.repository.checksum_verification = checksum_verification;
+        self
+    }
+
+    pub fn signature_verification(mut self, signature_verification: SignatureVerification) -> Self {
+        self.repository.signature_verification = signature_verification;
+        self
+    }
+
+    pub fn binary_permissions(mut self, binary_permissions: BinaryPermissions) -> Self {
+        self.repository.binary_permissions = binary_permissions;
+        self
+    }
+
+    /// Sets where versions of the release type are discovered from.
+    pub fn version_source(mut self, release_type: ReleaseType, source: VersionSource) -> Self {
+        self.repository.version_sources.insert(release_type, source);
+        self
+    }
+
+    /// Sets the most downloads `download_releases` runs at the same time.
+    pub fn max_concurrent_downloads(mut self, max_concurrent_downloads: usize) -> Self {
+        self.repository.max_concurrent_downloads = max_concurrent_downloads;
+        self
+    }
+
+    /// Builds the repository.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the HTTP client can't be built from the configured
+    /// `HttpConfig`.
+    pub fn build(self) -> Result<AntReleaseRepository> {
+        let mut repository = self.repository;
+        if let Some(http_config) = self.http_config {
+            repository.http_client = http_config.build_client()?;
+        }
+        Ok(repository)
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Result;
+use crate::{ArchiveType, Platform, ReleaseType};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{ErrorKind, Read};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+const BLOBS_DIR_NAME: &str = "blobs";
+const INDEX_DIR_NAME: &str = "index";
+/// Blobs that aren't referenced by the index are only removed once they are at least this old,
+/// to avoid removing one that another process has written but not yet indexed.
+const ORPHANED_BLOB_GRACE_PERIOD: Duration = Duration::from_secs(60);
+
+/// Limits on what is kept in a [`ReleaseCache`], applied by [`ReleaseCache::prune`].
+///
+/// Each limit is optional, and an entry is removed if it falls foul of any of them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CachePolicy {
+    /// The maximum combined size of the cached archives, in bytes. When exceeded, the entries
+    /// that were cached the longest time ago are removed first.
+    pub max_total_size: Option<u64>,
+    /// Entries that were cached longer ago than this are removed.
+    pub max_age: Option<Duration>,
+    /// The number of versions to keep for each release type. Older versions are removed.
+    pub keep_last_versions: Option<usize>,
+}
+
+/// An on-disk cache of downloaded release archives.
+///
+/// Archives are stored once, under the SHA-256 digest of their content, in a `blobs` directory.
+/// An `index` directory maps each release, identified by its type, version, platform and archive
+/// type, to the digest of its archive, using the layout
+/// `index/<release type>/<version>/<platform>.<archive type>`. The digest is followed, on a second
+/// line, by the verification policy the archive passed before it was cached.
+#[derive(Clone, Debug)]
+pub struct ReleaseCache {
+    root: PathBuf,
+    policy: CachePolicy,
+}
+
+/// A release archive held in the cache.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CacheEntry {
+    pub release_type: ReleaseType,
+    pub version: Version,
+    pub platform: Platform,
+    pub archive_type: ArchiveType,
+    /// The hex-encoded SHA-256 digest of the archive.
+    pub sha256: String,
+    pub size: u64,
+    /// The location of the archive within the cache.
+    pub path: PathBuf,
+    pub cached_at: SystemTime,
+    /// Identifies the checksum and signature verification the archive passed before it was
+    /// cached, so it isn't reused once verification has been made stricter. Entries cached before
+    /// this was recorded have an empty value.
+    pub verified_with: String,
+}
+
+impl ReleaseCache {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            policy: CachePolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: CachePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn policy(&self) -> &CachePolicy {
+        &self.policy
+    }
+
+    /// Looks up a release, returning `None` if it isn't in the cache.
+    pub fn get(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+    ) -> Result<Option<CacheEntry>> {
+        let index_path = self.index_path(release_type, version, platform, archive_type);
+        let (sha256, verified_with) = match fs::read_to_string(&index_path) {
+            Ok(contents) => {
+                let mut lines = contents.lines().map(str::trim);
+                let sha256 = lines.next().unwrap_or_default().to_string();
+                (sha256, lines.next().unwrap_or_default().to_string())
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let cached_at = match fs::metadata(&index_path) {
+            Ok(metadata) => metadata.modified()?,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let path = self.blob_path(&sha256);
+        let size = match fs::metadata(&path) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(Some(CacheEntry {
+            release_type: *release_type,
+            version: version.clone(),
+            platform: *platform,
+            archive_type: *archive_type,
+            sha256,
+            size,
+            path,
+            cached_at,
+            verified_with,
+        }))
+    }
+
+    /// Adds the archive at `archive_path` to the cache, replacing any existing entry for the
+    /// release. `verified_with` identifies the verification the archive has passed.
+    pub fn insert(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        archive_path: &Path,
+        verified_with: &str,
+    ) -> Result<CacheEntry> {
+        let sha256 = hash_file(archive_path)?;
+        let blob_path = self.blob_path(&sha256);
+        if !blob_path.exists() {
+            fs::create_dir_all(self.root.join(BLOBS_DIR_NAME))?;
+            let tmp_path = get_tmp_path(&blob_path);
+            fs::copy(archive_path, &tmp_path)?;
+            fs::rename(&tmp_path, &blob_path)?;
+        }
+
+        let index_path = self.index_path(release_type, version, platform, archive_type);
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp_path = get_tmp_path(&index_path);
+        fs::write(&tmp_path, format!("{sha256}\n{verified_with}\n"))?;
+        fs::rename(&tmp_path, &index_path)?;
+
+        Ok(CacheEntry {
+            release_type: *release_type,
+            version: version.clone(),
+            platform: *platform,
+            archive_type: *archive_type,
+            size: fs::metadata(&blob_path)?.len(),
+            sha256,
+            path: blob_path,
+            cached_at: fs::metadata(&index_path)?.modified()?,
+            verified_with: verified_with.to_string(),
+        })
+    }
+
+    /// Places a copy of a cached archive at `dest_path`.
+    ///
+    /// A hard link is used where possible, falling back to copying the file, for example when
+    /// the destination is on a different file system.
+    pub fn copy_to(&self, entry: &CacheEntry, dest_path: &Path) -> Result<()> {
+        remove_file_if_exists(dest_path)?;
+        if fs::hard_link(&entry.path, dest_path).is_err() {
+            fs::copy(&entry.path, dest_path)?;
+        }
+        Ok(())
+    }
+
+    /// Lists every release in the cache.
+    pub fn list(&self) -> Result<Vec<CacheEntry>> {
+        let mut entries = Vec::new();
+        for release_type_dir in read_dir_if_exists(&self.root.join(INDEX_DIR_NAME))? {
+            let Some(release_type) =
+                file_name(&release_type_dir).and_then(ReleaseType::from_binary_name)
+            else {
+                continue;
+            };
+            for version_dir in read_dir_if_exists(&release_type_dir)? {
+                let Some(version) = file_name(&version_dir).and_then(|v| Version::parse(v).ok())
+                else {
+                    continue;
+                };
+                for index_path in read_dir_if_exists(&version_dir)? {
+                    let Some((platform, archive_type)) = file_name(&index_path)
+                        .and_then(|name| name.split_once('.'))
+                        .and_then(|(platform, ext)| {
+                            Some((platform.parse().ok()?, ext.parse().ok()?))
+                        })
+                    else {
+                        continue;
+                    };
+                    if let Some(entry) =
+                        self.get(&release_type, &version, &platform, &archive_type)?
+                    {
+                        entries.push(entry);
+                    }
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Returns the total size, in bytes, of the archives in the cache.
+    ///
+    /// Archives shared by several releases are only counted once.
+    pub fn size(&self) -> Result<u64> {
+        Ok(total_size(self.list()?.iter()))
+    }
+
+    /// Removes the entries that fall outside the cache's policy, returning those that were removed.
+    ///
+    /// Index entries are removed before the archives they refer to, and archives are renamed
+    /// before being deleted, so another process reading the cache at the same time either sees a
+    /// complete entry or none at all. An archive that has already been opened or linked elsewhere
+    /// remains readable.
+    pub fn prune(&self) -> Result<Vec<CacheEntry>> {
+        let entries = self.list()?;
+        let now = SystemTime::now();
+        let mut to_remove = HashSet::new();
+
+        if let Some(max_age) = self.policy.max_age {
+            for (i, entry) in entries.iter().enumerate() {
+                let age = now.duration_since(entry.cached_at).unwrap_or_default();
+                if age > max_age {
+                    to_remove.insert(i);
+                }
+            }
+        }
+
+        if let Some(keep) = self.policy.keep_last_versions {
+            let mut versions: HashMap<&ReleaseType, HashSet<&Version>> = HashMap::new();
+            for entry in &entries {
+                versions
+                    .entry(&entry.release_type)
+                    .or_default()
+                    .insert(&entry.version);
+            }
+            for (i, entry) in entries.iter().enumerate() {
+                let newer_versions = versions[&entry.release_type]
+                    .iter()
+                    .filter(|version| **version >= &entry.version)
+                    .count();
+                if newer_versions > keep {
+                    to_remove.insert(i);
+                }
+            }
+        }
+
+        if let Some(max_total_size) = self.policy.max_total_size {
+            let mut remaining: Vec<usize> = (0..entries.len())
+                .filter(|i| !to_remove.contains(i))
+                .collect();
+            remaining.sort_by_key(|&i| entries[i].cached_at);
+            let mut remaining = remaining.into_iter();
+            while total_size(remaining.clone().map(|i| &entries[i])) > max_total_size {
+                match remaining.next() {
+                    Some(i) => {
+                        to_remove.insert(i);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        for (i, entry) in entries.into_iter().enumerate() {
+            if to_remove.contains(&i) {
+                self.remove_index_entry(&entry)?;
+                removed.push(entry);
+            }
+        }
+        self.remove_unreferenced_blobs(&removed)?;
+
+        Ok(removed)
+    }
+
+    /// Removes everything from the cache.
+    pub fn clear(&self) -> Result<()> {
+        for dir_name in [INDEX_DIR_NAME, BLOBS_DIR_NAME] {
+            match fs::remove_dir_all(self.root.join(dir_name)) {
+                Ok(()) => {}
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_index_entry(&self, entry: &CacheEntry) -> Result<()> {
+        let index_path = self.index_path(
+            &entry.release_type,
+            &entry.version,
+            &entry.platform,
+            &entry.archive_type,
+        );
+        remove_file_if_exists(&index_path)?;
+        // Tidy up the version directory if it's now empty. This fails harmlessly if it isn't.
+        if let Some(version_dir) = index_path.parent() {
+            let _ = fs::remove_dir(version_dir);
+        }
+        Ok(())
+    }
+
+    /// Removes blobs that are no longer referenced by the index.
+    ///
+    /// Blobs belonging to `removed` entries are removed straight away. Any other unreferenced
+    /// blobs are left for the grace period, in case they are still being added by another process.
+    fn remove_unreferenced_blobs(&self, removed: &[CacheEntry]) -> Result<()> {
+        let referenced: HashSet<String> =
+            self.list()?.into_iter().map(|entry| entry.sha256).collect();
+        let removed: HashSet<&str> = removed.iter().map(|entry| entry.sha256.as_str()).collect();
+
+        for blob_path in read_dir_if_exists(&self.root.join(BLOBS_DIR_NAME))? {
+            let Some(sha256) = file_name(&blob_path) else {
+                continue;
+            };
+            if referenced.contains(sha256) {
+                continue;
+            }
+            if !removed.contains(sha256) {
+                let age = fs::metadata(&blob_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+                if !matches!(age, Some(age) if age > ORPHANED_BLOB_GRACE_PERIOD) {
+                    continue;
+                }
+            }
+
+            // Renaming first means the blob disappears from its content address atomically,
+            // even on platforms where deleting a file that is open elsewhere fails.
+            let evicted_path = get_tmp_path(&blob_path);
+            match fs::rename(&blob_path, &evicted_path) {
+                Ok(()) => {
+                    let _ = fs::remove_file(&evicted_path);
+                }
+                Err(err) if err.kind() == ErrorKind::NotFound => {}
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Ok(())
+    }
+
+    fn blob_path(&self, sha256: &str) -> PathBuf {
+        self.root.join(BLOBS_DIR_NAME).join(sha256)
+    }
+
+    fn index_path(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+    ) -> PathBuf {
+        self.root
+            .join(INDEX_DIR_NAME)
+            .join(release_type.to_string())
+            .join(version.to_string())
+            .join(format!("{platform}.{archive_type}"))
+    }
+}
+
+/// Sums the size of the blobs referred to by `entries`, counting shared blobs once.
+fn total_size<'a>(entries: impl Iterator<Item = &'a CacheEntry>) -> u64 {
+    let mut seen = HashSet::new();
+    entries
+        .filter(|entry| seen.insert(&entry.sha256))
+        .map(|entry| entry.size)
+        .sum()
+}
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Returns a unique temporary path next to `path`, used to write files before renaming them into
+/// place, so that readers never observe a partially written file.
+fn get_tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(format!(".{}.{}.tmp", std::process::id(), fastrand::u64(..)));
+    PathBuf::from(tmp_path)
+}
+
+fn read_dir_if_exists(path: &Path) -> Result<Vec<PathBuf>> {
+    match fs::read_dir(path) {
+        Ok(entries) => Ok(entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|name| name.to_str())
+}
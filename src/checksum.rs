@@ -0,0 +1,61 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// The extension of the sidecar file published next to each archive, containing its SHA-256 digest.
+pub const CHECKSUM_FILE_EXTENSION: &str = "sha256";
+
+/// Determines whether archives downloaded from S3 are verified against a published checksum.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ChecksumVerification {
+    /// No checksum is fetched and the archive is not verified.
+    Disabled,
+    /// The archive is verified if a checksum has been published alongside it.
+    #[default]
+    IfAvailable,
+    /// The archive must have a published checksum and it must match.
+    Required,
+}
+
+/// Parses a SHA-256 digest in the format produced by `sha256sum`, i.e., the hex digest optionally
+/// followed by the file name. Only the digest is returned, in lowercase.
+pub fn parse_checksum(content: &str) -> Result<String> {
+    let digest = content
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| Error::InvalidChecksum(content.to_string()))?
+        .to_lowercase();
+    if digest.len() != 64 || !digest.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidChecksum(content.to_string()));
+    }
+    Ok(digest)
+}
+
+/// Feeds the content of an existing file into `hasher`.
+///
+/// Used when a download is resumed, so that the digest covers the bytes fetched previously.
+pub(crate) async fn update_from_file(hasher: &mut Sha256, path: &Path) -> Result<()> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(())
+}
+
+pub(crate) fn to_hex(hasher: Sha256) -> String {
+    format!("{:x}", hasher.finalize())
+}
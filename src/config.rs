@@ -0,0 +1,438 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::{AntReleaseRepositoryBuilder, HttpConfig, ReleaseCache, ReleaseType};
+use reqwest::Url;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Environment variables that configure a repository all start with this prefix.
+pub const ENV_VAR_PREFIX: &str = "ANT_RELEASES_";
+
+/// Settings that can be given both in a config file and in the environment, as pairs of the key
+/// used in the file and the environment variable name, without its prefix.
+///
+/// The URL of each release type can also be given, under `[urls]` in a file, e.g., `antnode`, or
+/// as a variable named after the release type, e.g., `ANT_RELEASES_NAT_DETECTION_URL`. Likewise
+/// its mirrors, under `[mirrors]` as a list, or as a comma-separated variable, e.g.,
+/// `ANT_RELEASES_ANTNODE_MIRRORS`.
+const SETTINGS: &[(&str, &str)] = &[
+    ("base_url", "BASE_URL"),
+    ("cache_dir", "CACHE_DIR"),
+    ("crates_io_url", "CRATES_IO_URL"),
+    ("github_api_url", "GITHUB_API_URL"),
+    ("github_repository", "GITHUB_REPOSITORY"),
+    ("github_token", "GITHUB_TOKEN"),
+    ("max_concurrent_downloads", "MAX_CONCURRENT_DOWNLOADS"),
+    ("mirror_cool_down_secs", "MIRROR_COOL_DOWN_SECS"),
+    ("winsw_url", "WINSW_URL"),
+    ("http.connect_timeout_secs", "CONNECT_TIMEOUT_SECS"),
+    ("http.max_redirects", "MAX_REDIRECTS"),
+    ("http.no_proxy", "NO_PROXY"),
+    ("http.proxy", "PROXY"),
+    ("http.read_timeout_secs", "READ_TIMEOUT_SECS"),
+    ("http.timeout_secs", "TIMEOUT_SECS"),
+    ("http.user_agent", "USER_AGENT"),
+];
+
+/// Overrides for the default configuration of a repository, read from a TOML file or from
+/// `ANT_RELEASES_*` environment variables.
+///
+/// A file looks like this, with every key optional:
+///
+/// ```toml
+/// base_url = "https://mirror.internal/releases"
+/// cache_dir = "/var/cache/ant-releases"
+/// crates_io_url = "https://mirror.internal/crates-io/api/v1"
+/// max_concurrent_downloads = 4
+///
+/// [urls]
+/// antnode = "https://mirror.internal/antnode"
+///
+/// [mirrors]
+/// antnode = ["https://mirror-2.internal/antnode", "https://mirror-3.internal/antnode"]
+///
+/// [http]
+/// proxy = "http://proxy.internal:3128"
+/// connect_timeout_secs = 10
+///
+/// [http.headers]
+/// X-Mirror-Token = "secret"
+/// ```
+///
+/// The environment variable for a key is its name in upper case, with the prefix, e.g.,
+/// `ANT_RELEASES_CRATES_IO_URL`, except that the `http.` keys drop the section, e.g.,
+/// `ANT_RELEASES_PROXY`, and the release type URLs and mirrors are named like
+/// `ANT_RELEASES_ANTNODE_URL` and `ANT_RELEASES_ANTNODE_MIRRORS`. Headers can only be set in a file.
+///
+/// `base_url` points every release type at the same location, and the URL of a release type under
+/// `[urls]` takes precedence over it. When the environment is merged over a file, a `base_url` in
+/// the environment replaces the file's `[urls]` as well. `antctl` and `antctld` are downloaded from
+/// the same location, so their URLs, and their mirrors, must not be set to different values.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RepositoryConfig {
+    pub base_url: Option<String>,
+    pub release_urls: HashMap<ReleaseType, String>,
+    pub release_mirrors: HashMap<ReleaseType, Vec<String>>,
+    pub cache_dir: Option<PathBuf>,
+    pub crates_io_url: Option<String>,
+    pub github_api_url: Option<String>,
+    pub github_repository: Option<String>,
+    pub github_token: Option<String>,
+    pub max_concurrent_downloads: Option<usize>,
+    pub mirror_cool_down: Option<Duration>,
+    pub winsw_url: Option<String>,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub max_redirects: Option<usize>,
+    pub proxy: Option<String>,
+    pub no_proxy: Option<String>,
+    pub user_agent: Option<String>,
+    pub headers: HashMap<String, String>,
+}
+
+impl RepositoryConfig {
+    /// Reads the overrides given by `ANT_RELEASES_*` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidConfigValue`, naming the variable, if a variable has an invalid
+    /// value, including one that isn't valid UTF-8, or isn't a recognised setting. Variables
+    /// without the `ANT_RELEASES_` prefix are ignored, whatever their contents.
+    pub fn from_env() -> Result<Self> {
+        let mut config = Self::default();
+        for (name, value) in std::env::vars_os() {
+            let name = name.to_string_lossy();
+            let Some(suffix) = name.strip_prefix(ENV_VAR_PREFIX) else {
+                continue;
+            };
+            let value = value
+                .to_str()
+                .ok_or_else(|| invalid_value(&name, "must be valid UTF-8"))?;
+            let key = SETTINGS
+                .iter()
+                .find(|(_, env_suffix)| *env_suffix == suffix)
+                .map(|(key, _)| key.to_string())
+                .or_else(|| {
+                    let (section, release_type) = match suffix.strip_suffix("_URL") {
+                        Some(release_type) => ("urls", release_type),
+                        None => ("mirrors", suffix.strip_suffix("_MIRRORS")?),
+                    };
+                    ReleaseType::all()
+                        .find(|candidate| get_env_name(candidate) == release_type)
+                        .map(|release_type| format!("{section}.{release_type}"))
+                });
+            match key {
+                Some(key) => config.set(&key, &name, value)?,
+                None => return Err(unrecognised_setting(&name)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads the overrides in a TOML config file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file can't be read, or isn't valid TOML
+    /// - A key has an invalid value or isn't a recognised setting, in which case
+    ///   `Error::InvalidConfigValue` names the key, e.g., `http.connect_timeout_secs`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let table: toml::Table = contents.parse().map_err(|err: toml::de::Error| {
+            Error::ConfigParseError(format!("{}: {}", path.display(), err.message()))
+        })?;
+
+        let mut config = Self::default();
+        let mut entries = Vec::new();
+        flatten_table("", &table, &mut entries);
+        for (key, value) in entries {
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(value) => value.to_string(),
+                // Lists are only used for mirrors, which are parsed from the same comma-separated
+                // form as their environment variables.
+                toml::Value::Array(values) if key.starts_with("mirrors.") => values
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| invalid_value(&key, "expected a list of URLs"))?
+                    .join(","),
+                _ => return Err(invalid_value(&key, "expected a string or an integer")),
+            };
+            config.set(&key, &key, &value)?;
+        }
+        Ok(config)
+    }
+
+    /// Returns the config with the settings in `overrides` replacing those in `self`.
+    ///
+    /// A `base_url` in `overrides` replaces the URLs of every release type in `self` too, since
+    /// otherwise those would still take precedence over it.
+    pub fn merge(mut self, overrides: RepositoryConfig) -> Self {
+        let release_urls = if overrides.base_url.is_some() {
+            overrides.release_urls
+        } else {
+            merge_release_settings(self.release_urls, overrides.release_urls)
+        };
+        let release_mirrors =
+            merge_release_settings(self.release_mirrors, overrides.release_mirrors);
+        self.headers.extend(overrides.headers);
+        Self {
+            base_url: overrides.base_url.or(self.base_url),
+            release_urls,
+            release_mirrors,
+            cache_dir: overrides.cache_dir.or(self.cache_dir),
+            crates_io_url: overrides.crates_io_url.or(self.crates_io_url),
+            github_api_url: overrides.github_api_url.or(self.github_api_url),
+            github_repository: overrides.github_repository.or(self.github_repository),
+            github_token: overrides.github_token.or(self.github_token),
+            max_concurrent_downloads: overrides
+                .max_concurrent_downloads
+                .or(self.max_concurrent_downloads),
+            mirror_cool_down: overrides.mirror_cool_down.or(self.mirror_cool_down),
+            winsw_url: overrides.winsw_url.or(self.winsw_url),
+            connect_timeout: overrides.connect_timeout.or(self.connect_timeout),
+            read_timeout: overrides.read_timeout.or(self.read_timeout),
+            timeout: overrides.timeout.or(self.timeout),
+            max_redirects: overrides.max_redirects.or(self.max_redirects),
+            proxy: overrides.proxy.or(self.proxy),
+            no_proxy: overrides.no_proxy.or(self.no_proxy),
+            user_agent: overrides.user_agent.or(self.user_agent),
+            headers: self.headers,
+        }
+    }
+
+    /// Applies the settings to a builder, leaving anything that isn't set as it was.
+    pub fn apply(self, mut builder: AntReleaseRepositoryBuilder) -> AntReleaseRepositoryBuilder {
+        if let Some(url) = self.base_url {
+            builder = builder.all_base_urls(url);
+        }
+        for (release_type, url) in self.release_urls {
+            builder = builder.base_url(release_type, url);
+        }
+        for (release_type, urls) in self.release_mirrors {
+            builder = builder.mirrors(release_type, urls);
+        }
+        if let Some(cache_dir) = self.cache_dir {
+            builder = builder.cache(ReleaseCache::new(cache_dir));
+        }
+        if let Some(url) = self.crates_io_url {
+            builder = builder.crates_io_api_base_url(url);
+        }
+        if let Some(url) = self.github_api_url {
+            builder = builder.github_api_base_url(url);
+        }
+        if let Some(repository) = self.github_repository {
+            builder = builder.github_repository(repository);
+        }
+        if let Some(token) = self.github_token {
+            builder = builder.github_token(token);
+        }
+        if let Some(max_concurrent_downloads) = self.max_concurrent_downloads {
+            builder = builder.max_concurrent_downloads(max_concurrent_downloads);
+        }
+        if let Some(cool_down) = self.mirror_cool_down {
+            builder = builder.mirror_cool_down(cool_down);
+        }
+        if let Some(url) = self.winsw_url {
+            builder = builder.winsw_url(url);
+        }
+
+        let default_http_config = HttpConfig::default();
+        let http_config = HttpConfig {
+            connect_timeout: self.connect_timeout.or(default_http_config.connect_timeout),
+            read_timeout: self.read_timeout.or(default_http_config.read_timeout),
+            timeout: self.timeout.or(default_http_config.timeout),
+            proxy: self.proxy,
+            no_proxy: self.no_proxy,
+            user_agent: self.user_agent.unwrap_or(default_http_config.user_agent),
+            headers: self.headers,
+            max_redirects: self
+                .max_redirects
+                .unwrap_or(default_http_config.max_redirects),
+        };
+        builder.http_config(http_config)
+    }
+
+    /// Sets the setting identified by its config file key, with `source` naming where the value
+    /// came from for any error.
+    fn set(&mut self, key: &str, source: &str, value: &str) -> Result<()> {
+        match key {
+            "base_url" => self.base_url = Some(parse_url(source, value)?),
+            "cache_dir" => self.cache_dir = Some(PathBuf::from(parse_non_empty(source, value)?)),
+            "crates_io_url" => self.crates_io_url = Some(parse_url(source, value)?),
+            "github_api_url" => self.github_api_url = Some(parse_url(source, value)?),
+            "github_repository" => {
+                if value.split('/').filter(|part| !part.is_empty()).count() != 2 {
+                    return Err(invalid_value(source, "expected the form owner/name"));
+                }
+                self.github_repository = Some(value.to_string())
+            }
+            "github_token" => self.github_token = Some(parse_non_empty(source, value)?),
+            "max_concurrent_downloads" => {
+                self.max_concurrent_downloads = Some(parse_positive(source, value)? as usize)
+            }
+            "mirror_cool_down_secs" => {
+                self.mirror_cool_down = Some(Duration::from_secs(parse_positive(source, value)?))
+            }
+            "winsw_url" => self.winsw_url = Some(parse_url(source, value)?),
+            "http.connect_timeout_secs" => {
+                self.connect_timeout = Some(Duration::from_secs(parse_positive(source, value)?))
+            }
+            "http.max_redirects" => {
+                let max_redirects = value
+                    .parse()
+                    .map_err(|_| invalid_value(source, "expected a whole number of redirects"))?;
+                self.max_redirects = Some(max_redirects)
+            }
+            "http.no_proxy" => self.no_proxy = Some(value.to_string()),
+            "http.proxy" => self.proxy = Some(parse_url(source, value)?),
+            "http.read_timeout_secs" => {
+                self.read_timeout = Some(Duration::from_secs(parse_positive(source, value)?))
+            }
+            "http.timeout_secs" => {
+                self.timeout = Some(Duration::from_secs(parse_positive(source, value)?))
+            }
+            "http.user_agent" => self.user_agent = Some(parse_non_empty(source, value)?),
+            _ => {
+                if let Some(name) = key.strip_prefix("urls.") {
+                    let release_type = ReleaseType::all()
+                        .find(|release_type| release_type.to_string() == name)
+                        .ok_or_else(|| unrecognised_setting(source))?;
+                    let url = parse_url(source, value)?;
+                    check_shared_setting(&self.release_urls, release_type, &url, source)?;
+                    self.release_urls.insert(release_type, url);
+                } else if let Some(name) = key.strip_prefix("mirrors.") {
+                    let release_type = ReleaseType::all()
+                        .find(|release_type| release_type.to_string() == name)
+                        .ok_or_else(|| unrecognised_setting(source))?;
+                    let urls = value
+                        .split(',')
+                        .filter(|url| !url.trim().is_empty())
+                        .map(|url| parse_url(source, url.trim()))
+                        .collect::<Result<Vec<_>>>()?;
+                    check_shared_setting(&self.release_mirrors, release_type, &urls, source)?;
+                    self.release_mirrors.insert(release_type, urls);
+                } else if let Some(name) = key.strip_prefix("http.headers.") {
+                    self.headers.insert(name.to_string(), value.to_string());
+                } else {
+                    return Err(unrecognised_setting(source));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects the values in a table, and in any tables nested within it, keyed by their dotted
+/// path, e.g., `http.proxy`.
+fn flatten_table<'a>(
+    prefix: &str,
+    table: &'a toml::Table,
+    entries: &mut Vec<(String, &'a toml::Value)>,
+) {
+    for (key, value) in table {
+        let key = format!("{prefix}{key}");
+        match value {
+            toml::Value::Table(table) => flatten_table(&format!("{key}."), table, entries),
+            value => entries.push((key, value)),
+        }
+    }
+}
+
+/// Returns the release type that shares its URL and mirrors with `release_type`, if there is one.
+/// `antctl` and `antctld` are published to the same bucket.
+fn get_shared_release_type(release_type: ReleaseType) -> Option<ReleaseType> {
+    match release_type {
+        ReleaseType::AntCtl => Some(ReleaseType::AntCtlDaemon),
+        ReleaseType::AntCtlDaemon => Some(ReleaseType::AntCtl),
+        _ => None,
+    }
+}
+
+/// Fails if the release type sharing its settings with `release_type` has already been given a
+/// different value, since only one of them could be applied.
+fn check_shared_setting<T: PartialEq>(
+    settings: &HashMap<ReleaseType, T>,
+    release_type: ReleaseType,
+    value: &T,
+    source: &str,
+) -> Result<()> {
+    let Some(shared_release_type) = get_shared_release_type(release_type) else {
+        return Ok(());
+    };
+    match settings.get(&shared_release_type) {
+        Some(shared_value) if shared_value != value => Err(invalid_value(
+            source,
+            &format!(
+                "{release_type} and {shared_release_type} are downloaded from the same location, \
+                 which is set differently for {shared_release_type}"
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Merges settings given per release type, with those in `overrides` taking precedence. Overriding
+/// a release type also replaces the setting of the release type it's shared with.
+fn merge_release_settings<T>(
+    mut settings: HashMap<ReleaseType, T>,
+    overrides: HashMap<ReleaseType, T>,
+) -> HashMap<ReleaseType, T> {
+    for release_type in overrides.keys() {
+        if let Some(shared_release_type) = get_shared_release_type(*release_type) {
+            settings.remove(&shared_release_type);
+        }
+    }
+    settings.extend(overrides);
+    settings
+}
+
+/// Returns the name a release type has in environment variables, e.g., `NAT_DETECTION`.
+fn get_env_name(release_type: &ReleaseType) -> String {
+    release_type.to_string().to_uppercase().replace('-', "_")
+}
+
+fn parse_url(source: &str, value: &str) -> Result<String> {
+    match Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {
+            Ok(value.trim_end_matches('/').to_string())
+        }
+        _ => Err(invalid_value(source, "expected an http or https URL")),
+    }
+}
+
+fn parse_positive(source: &str, value: &str) -> Result<u64> {
+    match value.parse::<u64>() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(invalid_value(source, "expected a positive whole number")),
+    }
+}
+
+fn parse_non_empty(source: &str, value: &str) -> Result<String> {
+    if value.trim().is_empty() {
+        return Err(invalid_value(source, "must not be empty"));
+    }
+    Ok(value.to_string())
+}
+
+fn invalid_value(key: &str, reason: &str) -> Error {
+    Error::InvalidConfigValue {
+        key: key.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+fn unrecognised_setting(key: &str) -> Error {
+    invalid_value(key, "not a recognised setting")
+}
@@ -6,6 +6,9 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+use crate::ArchiveType;
+use chrono::{DateTime, Utc};
+use semver::Version;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -13,34 +16,114 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Error)]
 #[allow(missing_docs)]
 pub enum Error {
+    #[error(
+        "Extracting {0} archives requires the `{}` feature",
+        .0.to_string().replace('.', "-")
+    )]
+    ArchiveTypeNotEnabled(ArchiveType),
+    #[error(
+        "{binary_name} was not found in the archive. Entries: {}",
+        format_entries(.entries)
+    )]
+    BinaryNotFoundInArchive {
+        binary_name: String,
+        entries: Vec<String>,
+    },
     #[error("Cannot parse file name from the URL")]
     CannotParseFilenameFromUrl,
+    #[error("Checksum mismatch: expected {expected}, got {actual}")]
+    ChecksumMismatch { expected: String, actual: String },
+    #[error("No checksum was published for {0}")]
+    ChecksumMissing(String),
+    #[error("Could not parse the config file {0}")]
+    ConfigParseError(String),
     #[error("Unexpected response from crates.io: {0}")]
     CratesIoResponseError(u16),
     #[error(transparent)]
     DateTimeParseError(#[from] chrono::ParseError),
+    #[error("The GitHub API rate limit of {limit} requests has been exhausted until {reset_at}")]
+    GitHubRateLimitExceeded { limit: u64, reset_at: DateTime<Utc> },
+    #[error("Could not parse the response from the GitHub API: {0}")]
+    GitHubResponseParseError(String),
     #[error("Could not convert API response header links to string")]
     HeaderLinksToStrError,
+    #[error("{0} is not the file name of a release archive")]
+    InvalidArchiveName(String),
+    #[error("{0} is not a supported archive type")]
+    InvalidArchiveType(String),
+    #[error("Invalid SHA-256 checksum: {0}")]
+    InvalidChecksum(String),
+    #[error("Invalid value for {key}: {reason}")]
+    InvalidConfigValue { key: String, reason: String },
+    #[error("The HTTP header {0} has an invalid name or value")]
+    InvalidHttpHeader(String),
+    #[error("{0} is not a supported platform")]
+    InvalidPlatform(String),
+    #[error("Could not parse the minisign public key: {0}")]
+    InvalidPublicKey(String),
+    #[error("{0} is not a release type")]
+    InvalidReleaseType(String),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
     #[error("Latest release not found for {0}")]
     LatestReleaseNotFound(String),
+    #[error(
+        "No version of {release_type} matches {version_req}. Available versions: {}",
+        format_versions(.candidates)
+    )]
+    NoMatchingVersion {
+        release_type: String,
+        version_req: String,
+        candidates: Vec<Version>,
+    },
     #[error("{0}")]
     PlatformNotSupported(String),
     #[error("Could not compile the regex statement")]
     RegexError,
+    #[error("{0} is not published to crates.io")]
+    ReleaseNotPublishedToCratesIo(String),
     #[error(transparent)]
     ReqwestError(#[from] reqwest::Error),
     #[error("Release binary {0} was not found")]
     ReleaseBinaryNotFound(String),
+    #[error("Could not parse the S3 bucket listing: {0}")]
+    S3ListingParseError(String),
     #[error(transparent)]
     SemVerError(#[from] semver::Error),
+    #[error("The signature for {0} was made for a different file")]
+    SignatureFileMismatch(String),
+    #[error("The signature for {0} is not valid for any of the trusted keys")]
+    SignatureInvalid(String),
+    #[error("No signature was published for {0}")]
+    SignatureMissing(String),
     #[error("Could not parse version from tag name")]
     TagNameVersionParsingFailed,
-    #[error("The URL must point to a zip or gzipped tar archive")]
+    #[error("Unexpected response status {0} from {1}")]
+    UnexpectedResponseStatus(u16, String),
+    #[error("Archive entry {0} would be extracted outside of the destination directory")]
+    UnsafeArchiveEntry(String),
+    #[error("The URL must point to a zip archive or a compressed tarball")]
     UrlIsNotArchive,
     #[error(transparent)]
     ZipError(#[from] zip::result::ZipError),
 }
+
+fn format_versions(versions: &[Version]) -> String {
+    if versions.is_empty() {
+        return "none".to_string();
+    }
+    versions
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_entries(entries: &[String]) -> String {
+    if entries.is_empty() {
+        return "none".to_string();
+    }
+    entries.join(", ")
+}
@@ -0,0 +1,569 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::ArchiveType;
+use bytes::Bytes;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tar::Archive;
+use tokio::sync::mpsc::Receiver;
+use zip::ZipArchive;
+
+/// A file or directory that was unpacked from a release archive.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtractedEntry {
+    /// The path of the entry within the archive, with `/` separators.
+    pub name: String,
+    /// Where the entry was unpacked to.
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// The number of bytes written, which is zero for directories.
+    pub size: u64,
+}
+
+/// How the permissions of an extracted release binary are set on Unix.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BinaryPermissions {
+    /// Use the mode recorded in the archive. A binary from an archive without Unix modes, such as
+    /// a zip made on Windows, gets the default permissions for a new file.
+    #[default]
+    FromArchive,
+    /// Always make the binary executable, with mode `0o755`, whatever the archive records.
+    Executable,
+}
+
+/// The entries unpacked from a release archive, in the order they appear in it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExtractionManifest {
+    pub entries: Vec<ExtractedEntry>,
+}
+
+impl ExtractionManifest {
+    /// Returns the files that were unpacked, leaving out directories.
+    pub fn files(&self) -> impl Iterator<Item = &ExtractedEntry> {
+        self.entries.iter().filter(|entry| !entry.is_dir)
+    }
+
+    /// Returns the unpacked file with the given file name, wherever it is in the archive.
+    pub fn find_file(&self, file_name: &str) -> Option<&ExtractedEntry> {
+        self.files()
+            .find(|entry| get_file_name(&entry.name) == file_name)
+    }
+}
+
+/// The kinds of archive entry that can be extracted.
+pub(crate) enum EntryKind {
+    File,
+    Dir,
+    /// A symbolic link to the given target, relative to the directory containing the link.
+    Symlink(PathBuf),
+    /// A hard link to the given entry, relative to the root of the archive.
+    Hardlink(PathBuf),
+}
+
+/// An entry being read from an archive.
+pub(crate) struct ArchiveEntry<'a> {
+    pub name: String,
+    pub kind: EntryKind,
+    /// The Unix permissions recorded for the entry, if any.
+    pub mode: Option<u32>,
+    /// The modification time recorded for the entry, if any.
+    pub mtime: Option<SystemTime>,
+    pub reader: &'a mut dyn Read,
+}
+
+/// Whether to carry on reading an archive after visiting an entry.
+pub(crate) enum Visit {
+    Continue,
+    Stop,
+}
+
+/// Calls `visitor` with each entry of a tar or zip archive, in order, until it returns
+/// `Visit::Stop` or the entries are exhausted.
+///
+/// The type of archive is determined from the extension of its file name.
+pub(crate) fn visit_entries<F>(archive_path: &Path, visitor: F) -> Result<()>
+where
+    F: FnMut(ArchiveEntry<'_>) -> Result<Visit>,
+{
+    if !archive_path.exists() {
+        return Err(Error::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("Archive not found at: {:?}", archive_path),
+        )));
+    }
+
+    let archive_type = archive_path
+        .file_name()
+        .and_then(|file_name| ArchiveType::from_file_name(&file_name.to_string_lossy()))
+        .ok_or_else(|| {
+            Error::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Unsupported archive format",
+            ))
+        })?;
+    let archive_file = File::open(archive_path)?;
+    match archive_type {
+        ArchiveType::Zip => visit_zip_entries(archive_file, visitor),
+        archive_type => visit_tar_entries(get_tar_decoder(archive_type, archive_file)?, visitor),
+    }
+}
+
+/// Wraps the compressed tarball read from `reader` in the decoder for its archive type.
+pub(crate) fn get_tar_decoder<'a, R: Read + 'a>(
+    archive_type: ArchiveType,
+    reader: R,
+) -> Result<Box<dyn Read + 'a>> {
+    match archive_type {
+        ArchiveType::TarGz => Ok(Box::new(flate2::read::GzDecoder::new(reader))),
+        #[cfg(feature = "tar-xz")]
+        ArchiveType::TarXz => Ok(Box::new(xz2::read::XzDecoder::new_multi_decoder(reader))),
+        #[cfg(feature = "tar-zst")]
+        ArchiveType::TarZst => Ok(Box::new(zstd::stream::read::Decoder::new(reader)?)),
+        #[cfg(feature = "tar-bz2")]
+        ArchiveType::TarBz2 => Ok(Box::new(bzip2::read::MultiBzDecoder::new(reader))),
+        archive_type => Err(Error::ArchiveTypeNotEnabled(archive_type)),
+    }
+}
+
+/// Calls `visitor` with each entry of a decompressed tarball.
+pub(crate) fn visit_tar_entries<R, F>(tarball: R, mut visitor: F) -> Result<()>
+where
+    R: Read,
+    F: FnMut(ArchiveEntry<'_>) -> Result<Visit>,
+{
+    let mut archive = Archive::new(tarball);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_type = entry.header().entry_type();
+        let link_name = entry.link_name()?.map(|link_name| link_name.into_owned());
+        let kind = match (entry_type, link_name) {
+            (entry_type, _) if entry_type.is_file() => EntryKind::File,
+            (entry_type, _) if entry_type.is_dir() => EntryKind::Dir,
+            (entry_type, Some(target)) if entry_type.is_symlink() => EntryKind::Symlink(target),
+            (entry_type, Some(target)) if entry_type.is_hard_link() => EntryKind::Hardlink(target),
+            _ => continue,
+        };
+        let name = entry.path()?.to_string_lossy().into_owned();
+        let mode = entry.header().mode().ok();
+        let mtime = entry
+            .header()
+            .mtime()
+            .ok()
+            .map(|mtime| UNIX_EPOCH + Duration::from_secs(mtime));
+        let visit = visitor(ArchiveEntry {
+            name,
+            kind,
+            mode,
+            mtime,
+            reader: &mut entry,
+        })?;
+        if let Visit::Stop = visit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Calls `visitor` with each entry of a zip archive.
+pub(crate) fn visit_zip_entries<R, F>(reader: R, mut visitor: F) -> Result<()>
+where
+    R: Read + std::io::Seek,
+    F: FnMut(ArchiveEntry<'_>) -> Result<Visit>,
+{
+    let mut archive = ZipArchive::new(reader)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let name = file.name().to_string();
+        let kind = if file.is_dir() {
+            EntryKind::Dir
+        } else if file
+            .unix_mode()
+            .is_some_and(|mode| mode & S_IFMT == S_IFLNK)
+        {
+            // Zip archives made on Unix store the target of a symlink as its contents.
+            let mut target = String::new();
+            file.read_to_string(&mut target)?;
+            EntryKind::Symlink(PathBuf::from(target))
+        } else {
+            EntryKind::File
+        };
+        let mode = file.unix_mode();
+        let mtime = get_zip_mtime(&file.last_modified());
+        let visit = visitor(ArchiveEntry {
+            name,
+            kind,
+            mode,
+            mtime,
+            reader: &mut file,
+        })?;
+        if let Visit::Stop = visit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// The directory an archive is extracted to, which no entry may be written outside of.
+pub(crate) struct Destination {
+    path: PathBuf,
+    canonical_path: PathBuf,
+}
+
+impl Destination {
+    pub fn new(path: &Path) -> Result<Self> {
+        std::fs::create_dir_all(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            canonical_path: path.canonicalize()?,
+        })
+    }
+
+    /// Returns where an entry should be unpacked to, or `None` for an entry that refers to the
+    /// destination itself, such as `./`.
+    fn get_entry_path(&self, name: &str) -> Result<Option<PathBuf>> {
+        let relative_path = get_safe_relative_path(name)?;
+        if relative_path.as_os_str().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.path.join(relative_path)))
+    }
+
+    /// Creates the parent directory of `out_path`, then checks it hasn't been redirected outside
+    /// the destination by a symlink that was extracted earlier.
+    fn create_parent(&self, name: &str, out_path: &Path) -> Result<PathBuf> {
+        let parent = out_path.parent().unwrap_or(&self.path);
+        std::fs::create_dir_all(parent)?;
+        let canonical_parent = parent.canonicalize()?;
+        if !canonical_parent.starts_with(&self.canonical_path) {
+            return Err(Error::UnsafeArchiveEntry(name.to_string()));
+        }
+        Ok(canonical_parent)
+    }
+
+    /// Checks that a symlink in `canonical_parent` pointing at `target` resolves to a path
+    /// within the destination.
+    ///
+    /// The target may only step up with `..` at its start, which means the symlinks it passes
+    /// through can only be ones that were themselves checked when they were extracted.
+    fn check_symlink_target(
+        &self,
+        name: &str,
+        canonical_parent: &Path,
+        target: &Path,
+    ) -> Result<()> {
+        let unsafe_entry = || Error::UnsafeArchiveEntry(name.to_string());
+        let mut resolved = canonical_parent.to_path_buf();
+        let mut descended = false;
+        for component in target.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir if !descended => {
+                    if !resolved.pop() {
+                        return Err(unsafe_entry());
+                    }
+                }
+                Component::Normal(part) => {
+                    descended = true;
+                    resolved.push(part);
+                }
+                Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                    return Err(unsafe_entry())
+                }
+            }
+        }
+        if !resolved.starts_with(&self.canonical_path) {
+            return Err(unsafe_entry());
+        }
+        Ok(())
+    }
+}
+
+/// Converts the name of an archive entry to a relative path, rejecting absolute paths and any
+/// `..` components, which could be used to write outside the destination.
+fn get_safe_relative_path(name: &str) -> Result<PathBuf> {
+    // Archives made on Windows can use backslashes as separators.
+    let normalized = name.replace('\\', "/");
+    let mut relative_path = PathBuf::new();
+    for component in Path::new(&normalized).components() {
+        match component {
+            Component::Normal(part) => relative_path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(Error::UnsafeArchiveEntry(name.to_string()))
+            }
+        }
+    }
+    // A drive letter, e.g., `C:`, is only recognised as a prefix on Windows.
+    if normalized.len() >= 2 && normalized.as_bytes()[1] == b':' {
+        return Err(Error::UnsafeArchiveEntry(name.to_string()));
+    }
+    Ok(relative_path)
+}
+
+/// Unpacks an entry to `out_path`, creating any missing parent directories.
+///
+/// Entries that would be written outside the destination are rejected with an
+/// `UnsafeArchiveEntry` error.
+fn unpack_entry(
+    entry: ArchiveEntry<'_>,
+    dest: &Destination,
+    out_path: &Path,
+) -> Result<ExtractedEntry> {
+    let canonical_parent = dest.create_parent(&entry.name, out_path)?;
+    if let Ok(metadata) = std::fs::symlink_metadata(out_path) {
+        // Never write through a link that's already there.
+        if !metadata.is_dir() {
+            std::fs::remove_file(out_path)?;
+        }
+    }
+
+    let mut size = 0;
+    match &entry.kind {
+        EntryKind::Dir => std::fs::create_dir_all(out_path)?,
+        EntryKind::File => {
+            let mut out_file = File::create(out_path)?;
+            size = std::io::copy(entry.reader, &mut out_file)?;
+            if let Some(mode) = entry.mode {
+                set_mode(&out_file, mode)?;
+            }
+            if let Some(mtime) = entry.mtime {
+                out_file.set_modified(mtime)?;
+            }
+        }
+        EntryKind::Symlink(target) => {
+            dest.check_symlink_target(&entry.name, &canonical_parent, target)?;
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(target, out_path)?;
+        }
+        EntryKind::Hardlink(target) => {
+            let target_name = target.to_string_lossy();
+            let target_path = dest
+                .get_entry_path(&target_name)
+                .map_err(|_| Error::UnsafeArchiveEntry(entry.name.clone()))?
+                .ok_or_else(|| Error::UnsafeArchiveEntry(entry.name.clone()))?;
+            let canonical_target = target_path.canonicalize()?;
+            if !canonical_target.starts_with(&dest.canonical_path) {
+                return Err(Error::UnsafeArchiveEntry(entry.name));
+            }
+            std::fs::hard_link(&canonical_target, out_path)?;
+            size = std::fs::metadata(out_path)?.len();
+        }
+    }
+    Ok(ExtractedEntry {
+        is_dir: matches!(entry.kind, EntryKind::Dir),
+        name: entry.name,
+        path: out_path.to_path_buf(),
+        size,
+    })
+}
+
+/// Unpacks the first entry of an archive.
+pub(crate) fn extract_first(
+    archive_path: &Path,
+    dest_dir_path: &Path,
+    permissions: BinaryPermissions,
+) -> Result<PathBuf> {
+    let dest = Destination::new(dest_dir_path)?;
+    let mut extracted = None;
+    visit_entries(archive_path, |entry| {
+        let Some(out_path) = dest.get_entry_path(&entry.name)? else {
+            return Ok(Visit::Continue);
+        };
+        let entry = unpack_entry(entry, &dest, &out_path)?;
+        if !entry.is_dir {
+            apply_binary_permissions(&entry.path, permissions)?;
+        }
+        extracted = Some(entry.path);
+        Ok(Visit::Stop)
+    })?;
+    extracted.ok_or_else(|| Error::Io(std::io::Error::other("Failed to extract archive")))
+}
+
+/// Unpacks every entry of an archive, keeping the directory structure.
+pub(crate) fn extract_all(archive_path: &Path, dest_dir_path: &Path) -> Result<ExtractionManifest> {
+    let dest = Destination::new(dest_dir_path)?;
+    let mut manifest = ExtractionManifest::default();
+    visit_entries(archive_path, |entry| {
+        if let Some(out_path) = dest.get_entry_path(&entry.name)? {
+            manifest
+                .entries
+                .push(unpack_entry(entry, &dest, &out_path)?);
+        }
+        Ok(Visit::Continue)
+    })?;
+    Ok(manifest)
+}
+
+/// Unpacks the binary with the given name directly into `dest_dir_path`, wherever it is in the
+/// archive. A name without an extension also matches a Windows binary with the `.exe` extension.
+pub(crate) fn extract_binary(
+    archive_path: &Path,
+    binary_name: &str,
+    dest_dir_path: &Path,
+    permissions: BinaryPermissions,
+) -> Result<PathBuf> {
+    extract_binary_with(
+        |visitor| visit_entries(archive_path, visitor),
+        binary_name,
+        dest_dir_path,
+        permissions,
+        || {},
+    )
+}
+
+/// Unpacks the binary with the given name from a compressed tarball that is read as a stream,
+/// in the same way as `extract_binary`.
+///
+/// `on_binary_reached` is called when the binary's entry is reached, before it is unpacked. The
+/// reader isn't read any further once the binary has been unpacked.
+pub(crate) fn extract_binary_from_tarball<R: Read>(
+    archive_type: ArchiveType,
+    reader: R,
+    binary_name: &str,
+    dest_dir_path: &Path,
+    permissions: BinaryPermissions,
+    on_binary_reached: impl FnOnce(),
+) -> Result<PathBuf> {
+    extract_binary_with(
+        |visitor| visit_tar_entries(get_tar_decoder(archive_type, reader)?, visitor),
+        binary_name,
+        dest_dir_path,
+        permissions,
+        on_binary_reached,
+    )
+}
+
+fn extract_binary_with<V>(
+    visit: V,
+    binary_name: &str,
+    dest_dir_path: &Path,
+    permissions: BinaryPermissions,
+    on_binary_reached: impl FnOnce(),
+) -> Result<PathBuf>
+where
+    V: FnOnce(&mut dyn FnMut(ArchiveEntry<'_>) -> Result<Visit>) -> Result<()>,
+{
+    let dest = Destination::new(dest_dir_path)?;
+    let mut on_binary_reached = Some(on_binary_reached);
+    let mut extracted = None;
+    let mut entries = Vec::new();
+    visit(&mut |entry| {
+        if !matches!(entry.kind, EntryKind::File) {
+            return Ok(Visit::Continue);
+        }
+        // The binary is extracted to the top of the destination, but it's still checked for an
+        // unsafe path, which would indicate a malicious archive.
+        get_safe_relative_path(&entry.name)?;
+        let file_name = get_file_name(&entry.name);
+        if !is_binary_named(file_name, binary_name) {
+            entries.push(entry.name);
+            return Ok(Visit::Continue);
+        }
+        if let Some(on_binary_reached) = on_binary_reached.take() {
+            on_binary_reached();
+        }
+        let out_path = dest_dir_path.join(file_name);
+        let entry = unpack_entry(entry, &dest, &out_path)?;
+        apply_binary_permissions(&entry.path, permissions)?;
+        extracted = Some(entry.path);
+        Ok(Visit::Stop)
+    })?;
+    extracted.ok_or_else(|| Error::BinaryNotFoundInArchive {
+        binary_name: binary_name.to_string(),
+        entries,
+    })
+}
+
+/// Presents the chunks of a download, received from an async task, as a blocking `Read`.
+///
+/// The end of the data is reached when the sending half of the channel is dropped.
+pub(crate) struct ChunkReader {
+    receiver: Receiver<Bytes>,
+    chunk: Bytes,
+}
+
+impl ChunkReader {
+    pub fn new(receiver: Receiver<Bytes>) -> Self {
+        Self {
+            receiver,
+            chunk: Bytes::new(),
+        }
+    }
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.chunk.is_empty() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => self.chunk = chunk,
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.chunk.len());
+        buf[..len].copy_from_slice(&self.chunk.split_to(len));
+        Ok(len)
+    }
+}
+
+fn is_binary_named(file_name: &str, binary_name: &str) -> bool {
+    file_name == binary_name
+        || file_name
+            .strip_suffix(".exe")
+            .is_some_and(|stem| stem == binary_name)
+}
+
+fn apply_binary_permissions(path: &Path, permissions: BinaryPermissions) -> Result<()> {
+    if permissions == BinaryPermissions::Executable {
+        set_mode(&File::open(path)?, 0o755)?;
+    }
+    Ok(())
+}
+
+/// Sets the permission bits of a file on Unix, leaving out the setuid, setgid and sticky bits.
+/// This does nothing on other platforms.
+#[allow(unused_variables)]
+fn set_mode(file: &File, mode: u32) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(mode & 0o777))?;
+    }
+    Ok(())
+}
+
+/// Converts the modification time of a zip entry, which has no time zone, treating it as UTC.
+fn get_zip_mtime(datetime: &zip::DateTime) -> Option<SystemTime> {
+    let mtime = chrono::NaiveDate::from_ymd_opt(
+        datetime.year().into(),
+        datetime.month().into(),
+        datetime.day().into(),
+    )?
+    .and_hms_opt(
+        datetime.hour().into(),
+        datetime.minute().into(),
+        datetime.second().into(),
+    )?
+    .and_utc();
+    Some(mtime.into())
+}
+
+/// File type bits of a Unix mode, and the value they have for a symlink.
+const S_IFMT: u32 = 0o170000;
+const S_IFLNK: u32 = 0o120000;
+
+fn get_file_name(entry_name: &str) -> &str {
+    entry_name
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(entry_name)
+}
@@ -0,0 +1,194 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::{ArchiveType, Platform, ReleaseArtifact, ReleaseType};
+use chrono::{DateTime, TimeZone, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use semver::Version;
+use serde_json::Value;
+use std::collections::HashMap;
+
+lazy_static! {
+    static ref LINK_REGEX: Regex =
+        Regex::new(r#"<([^>]+)>\s*;\s*rel="([^"]+)""#).expect("the link pattern is a valid regex");
+}
+
+/// The repository the releases are published to, in `owner/name` form.
+pub const DEFAULT_GITHUB_REPOSITORY: &str = "maidsafe/autonomi";
+
+/// A published release on GitHub, whose tag names a release type and version, e.g.,
+/// `antnode-v0.112.7`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    pub release_type: ReleaseType,
+    pub version: Version,
+    pub prerelease: bool,
+    pub published_at: DateTime<Utc>,
+    pub assets: Vec<GitHubReleaseAsset>,
+}
+
+impl GitHubRelease {
+    /// Returns the archive of this release for the given platform and archive type, if one was
+    /// attached to it.
+    pub fn find_asset(
+        &self,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+    ) -> Option<&GitHubReleaseAsset> {
+        self.assets.iter().find(|asset| {
+            matches!(
+                ReleaseArtifact::parse(&asset.name),
+                Ok(artifact)
+                    if artifact.release_type == self.release_type
+                        && artifact.version == self.version
+                        && artifact.platform == *platform
+                        && artifact.archive_type == *archive_type
+            )
+        })
+    }
+
+    /// Returns the platforms this release has an archive for.
+    pub fn platforms(&self) -> Vec<Platform> {
+        let mut platforms = Vec::new();
+        for asset in &self.assets {
+            if let Ok(artifact) = ReleaseArtifact::parse(&asset.name) {
+                if !platforms.contains(&artifact.platform) {
+                    platforms.push(artifact.platform);
+                }
+            }
+        }
+        platforms
+    }
+}
+
+/// A file attached to a GitHub release.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GitHubReleaseAsset {
+    pub name: String,
+    pub size: u64,
+    pub download_url: String,
+    /// The SHA-256 digest of the file, which GitHub reports for assets uploaded since mid-2025.
+    pub sha256: Option<String>,
+}
+
+/// Parses a release tag name, e.g., `antnode-v0.112.7` or `nat-detection-v0.2.11-rc.1`, into the
+/// release type and version it names.
+pub fn parse_tag_name(tag_name: &str) -> Result<(ReleaseType, Version)> {
+    // Release types can contain dashes, so try each `-v` in the name as the separator.
+    for (i, _) in tag_name.match_indices("-v") {
+        if let (Some(release_type), Ok(version)) = (
+            ReleaseType::from_binary_name(&tag_name[..i]),
+            Version::parse(&tag_name[i + 2..]),
+        ) {
+            return Ok((release_type, version));
+        }
+    }
+    Err(Error::TagNameVersionParsingFailed)
+}
+
+/// Parses the JSON array returned by the releases API.
+///
+/// Drafts, and releases whose tags don't name a release type and version, are skipped.
+pub(crate) fn parse_releases(json: &Value) -> Result<Vec<GitHubRelease>> {
+    let releases = json.as_array().ok_or_else(|| {
+        Error::GitHubResponseParseError("expected an array of releases".to_string())
+    })?;
+    let mut parsed = Vec::new();
+    for release in releases {
+        if release["draft"].as_bool().unwrap_or(false) {
+            continue;
+        }
+        if let Some(release) = parse_release(release)? {
+            parsed.push(release);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parses a single release returned by the releases API.
+///
+/// Returns `None` if the tag doesn't name a release type and version.
+pub(crate) fn parse_release(json: &Value) -> Result<Option<GitHubRelease>> {
+    let tag_name = json["tag_name"]
+        .as_str()
+        .ok_or_else(|| Error::GitHubResponseParseError("release without a tag name".into()))?;
+    let Ok((release_type, version)) = parse_tag_name(tag_name) else {
+        return Ok(None);
+    };
+    let published_at = json["published_at"].as_str().ok_or_else(|| {
+        Error::GitHubResponseParseError(format!("no publication date for {tag_name}"))
+    })?;
+
+    let mut assets = Vec::new();
+    for asset in json["assets"].as_array().into_iter().flatten() {
+        let (Some(name), Some(download_url)) = (
+            asset["name"].as_str(),
+            asset["browser_download_url"].as_str(),
+        ) else {
+            continue;
+        };
+        assets.push(GitHubReleaseAsset {
+            name: name.to_string(),
+            size: asset["size"].as_u64().unwrap_or(0),
+            download_url: download_url.to_string(),
+            sha256: asset["digest"]
+                .as_str()
+                .and_then(|digest| digest.strip_prefix("sha256:"))
+                .map(|digest| digest.to_lowercase()),
+        });
+    }
+
+    Ok(Some(GitHubRelease {
+        tag_name: tag_name.to_string(),
+        release_type,
+        version,
+        prerelease: json["prerelease"].as_bool().unwrap_or(false),
+        published_at: DateTime::parse_from_rfc3339(published_at)?.with_timezone(&Utc),
+        assets,
+    }))
+}
+
+/// Returns the URL of the next page from the `Link` header of a paginated response.
+pub(crate) fn get_next_page_url(headers: &HeaderMap) -> Result<Option<String>> {
+    let Some(link) = headers.get("link") else {
+        return Ok(None);
+    };
+    let link = link.to_str().map_err(|_| Error::HeaderLinksToStrError)?;
+    Ok(parse_link_header(link).remove("next"))
+}
+
+/// Parses a `Link` header, e.g., `<https://api.github.com/...&page=2>; rel="next"`, into a map
+/// from each relation to its URL.
+fn parse_link_header(link: &str) -> HashMap<String, String> {
+    LINK_REGEX
+        .captures_iter(link)
+        .map(|captures| (captures[2].to_string(), captures[1].to_string()))
+        .collect()
+}
+
+/// Returns a rate limit error if the response was rejected because the rate limit is exhausted.
+pub(crate) fn get_rate_limit_error(status: StatusCode, headers: &HeaderMap) -> Option<Error> {
+    if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+        return None;
+    }
+    let get_header = |name: &str| -> Option<i64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    if get_header("x-ratelimit-remaining")? != 0 {
+        return None;
+    }
+    Some(Error::GitHubRateLimitExceeded {
+        limit: get_header("x-ratelimit-limit").unwrap_or(0) as u64,
+        reset_at: Utc
+            .timestamp_opt(get_header("x-ratelimit-reset")?, 0)
+            .single()?,
+    })
+}
@@ -0,0 +1,112 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{redirect, Client, NoProxy, Proxy};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The user agent sent with every request unless configured otherwise. crates.io asks that
+/// clients identify themselves and give a way to contact the maintainers.
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// Settings for the HTTP client a repository makes all of its requests with.
+///
+/// Unless `proxy` is set, the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment variables
+/// are respected, along with `NO_PROXY` for hosts that should be reached directly.
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    /// How long to wait for a connection to be established.
+    pub connect_timeout: Option<Duration>,
+    /// How long to wait for each read from the server, which catches downloads that have stalled
+    /// without limiting how long a large archive can take.
+    pub read_timeout: Option<Duration>,
+    /// The most time a request can take from start to finish, including reading the body.
+    pub timeout: Option<Duration>,
+    /// The URL of a proxy to send every request through, e.g., `http://proxy.internal:3128`.
+    pub proxy: Option<String>,
+    /// Hosts that bypass `proxy`, as a comma-separated list in the same form as `NO_PROXY`. When
+    /// not set, `NO_PROXY` is used.
+    pub no_proxy: Option<String>,
+    pub user_agent: String,
+    /// Headers added to every request, e.g., for authenticating with a mirror.
+    pub headers: HashMap<String, String>,
+    /// The most redirects followed for a request. A value of 0 disables redirects.
+    pub max_redirects: usize,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Some(Duration::from_secs(30)),
+            read_timeout: Some(Duration::from_secs(60)),
+            timeout: None,
+            proxy: None,
+            no_proxy: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: HashMap::new(),
+            max_redirects: 10,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Builds a client from the settings.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - A header name or value is invalid
+    /// - The proxy URL can't be parsed
+    /// - The client can't be initialised, e.g., because the TLS backend failed to load
+    pub fn build_client(&self) -> Result<Client> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let invalid_header = || Error::InvalidHttpHeader(name.clone());
+            headers.insert(
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| invalid_header())?,
+                HeaderValue::from_str(value).map_err(|_| invalid_header())?,
+            );
+        }
+
+        let redirect_policy = if self.max_redirects == 0 {
+            redirect::Policy::none()
+        } else {
+            redirect::Policy::limited(self.max_redirects)
+        };
+        let mut builder = Client::builder()
+            .user_agent(&self.user_agent)
+            .default_headers(headers)
+            .redirect(redirect_policy);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            builder = builder.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(proxy_url) = &self.proxy {
+            let no_proxy = match &self.no_proxy {
+                Some(no_proxy) => NoProxy::from_string(no_proxy),
+                None => NoProxy::from_env(),
+            };
+            builder = builder.proxy(Proxy::all(proxy_url)?.no_proxy(no_proxy));
+        }
+        Ok(builder.build()?)
+    }
+}
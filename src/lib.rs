@@ -6,35 +6,75 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
+pub use crate::artifact::ReleaseArtifact;
+pub use crate::batch::{
+    BatchDownloadResult, BatchProgressCallback, DownloadTarget, VersionSelector,
+};
+pub use crate::builder::AntReleaseRepositoryBuilder;
+pub use crate::cache::{CacheEntry, CachePolicy, ReleaseCache};
+pub use crate::checksum::ChecksumVerification;
+pub use crate::config::RepositoryConfig;
 pub use crate::error::{Error, Result};
+pub use crate::extract::{BinaryPermissions, ExtractedEntry, ExtractionManifest};
+pub use crate::github::{GitHubRelease, GitHubReleaseAsset};
+pub use crate::http::HttpConfig;
+pub use crate::mirror::MirrorHealth;
+pub use crate::progress::{ByteProgress, ProgressEvent, ProgressHandler};
+pub use crate::retry::{RetryCallback, RetryPolicy};
+pub use crate::s3::{S3Object, S3ReleaseEntry, S3ReleaseIndex};
+pub use crate::signature::SignatureVerification;
 
+pub mod artifact;
+pub mod batch;
+pub mod builder;
+pub mod cache;
+pub mod checksum;
+pub mod config;
 pub mod error;
+pub mod extract;
+pub mod github;
+pub mod http;
+pub mod mirror;
+pub mod progress;
+pub mod retry;
+pub mod s3;
+pub mod signature;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use extract::ChunkReader;
+use futures::stream::{self, StreamExt};
 use lazy_static::lazy_static;
-use reqwest::Client;
-use semver::Version;
+use reqwest::{
+    header::{ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    Client, StatusCode,
+};
+use semver::{Version, VersionReq};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::env::consts::{ARCH, OS};
 use std::fmt;
+use std::future::Future;
 use std::path::{Path, PathBuf};
-use tar::Archive;
-use tokio::fs::File;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs::{File, OpenOptions};
 use tokio::io::AsyncWriteExt;
-use zip::ZipArchive;
 
 const ANTCTL_S3_BASE_URL: &str = "https://antctl.s3.eu-west-2.amazonaws.com";
 const ANTNODE_S3_BASE_URL: &str = "https://antnode.s3.eu-west-2.amazonaws.com";
 const ANTNODE_RPC_CLIENT_S3_BASE_URL: &str =
     "https://antnode-rpc-client.s3.eu-west-2.amazonaws.com";
 const ANT_S3_BASE_URL: &str = "https://autonomi-cli.s3.eu-west-2.amazonaws.com";
+const CRATES_IO_API_URL: &str = "https://crates.io/api/v1";
 const GITHUB_API_URL: &str = "https://api.github.com";
 const NAT_DETECTION_S3_BASE_URL: &str = "https://nat-detection.s3.eu-west-2.amazonaws.com";
 const NODE_LAUNCHPAD_S3_BASE_URL: &str = "https://node-launchpad.s3.eu-west-2.amazonaws.com";
 const WINSW_URL: &str = "https://sn-node-manager.s3.eu-west-2.amazonaws.com/WinSW-x64.exe";
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ReleaseType {
     Ant,
     AntCtl,
@@ -45,6 +85,40 @@ pub enum ReleaseType {
     NodeLaunchpad,
 }
 
+impl ReleaseType {
+    /// Returns every release type.
+    pub fn all() -> impl Iterator<Item = ReleaseType> {
+        [
+            ReleaseType::Ant,
+            ReleaseType::AntCtl,
+            ReleaseType::AntCtlDaemon,
+            ReleaseType::AntNode,
+            ReleaseType::AntNodeRpcClient,
+            ReleaseType::NatDetection,
+            ReleaseType::NodeLaunchpad,
+        ]
+        .into_iter()
+    }
+
+    /// Returns the release type with the given binary name, as produced by its `Display`
+    /// implementation.
+    ///
+    /// Names parsed from archives, tags and the cache must use this rather than `from_str`, so
+    /// that a crate name, which may be shared by several release types, is never mistaken for one.
+    pub(crate) fn from_binary_name(name: &str) -> Option<ReleaseType> {
+        ReleaseType::all().find(|release_type| release_type.to_string() == name)
+    }
+
+    /// Returns the release type published as the crate with the given name, e.g., `ant-node`.
+    ///
+    /// The node manager and its daemon are published in the same crate, so `ant-node-manager`
+    /// gives `AntCtl`.
+    pub fn from_crate_name(crate_name: &str) -> Option<ReleaseType> {
+        ReleaseType::all()
+            .find(|release_type| RELEASE_TYPE_CRATE_NAME_MAP.get(release_type) == Some(&crate_name))
+    }
+}
+
 impl fmt::Display for ReleaseType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -63,6 +137,21 @@ impl fmt::Display for ReleaseType {
     }
 }
 
+impl FromStr for ReleaseType {
+    type Err = Error;
+
+    /// Parses either the binary name of a release type, as produced by its `Display`
+    /// implementation, or the name of the crate it is published as.
+    ///
+    /// The node manager and its daemon are published in the same crate, so `ant-node-manager`
+    /// parses as `AntCtl`.
+    fn from_str(s: &str) -> Result<Self> {
+        ReleaseType::from_binary_name(s)
+            .or_else(|| ReleaseType::from_crate_name(s))
+            .ok_or_else(|| Error::InvalidReleaseType(s.to_string()))
+    }
+}
+
 lazy_static! {
     static ref RELEASE_TYPE_CRATE_NAME_MAP: HashMap<ReleaseType, &'static str> = {
         let mut m = HashMap::new();
@@ -77,7 +166,7 @@ lazy_static! {
     };
 }
 
-#[derive(Clone, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Platform {
     LinuxMusl,
     LinuxMuslAarch64,
@@ -88,6 +177,22 @@ pub enum Platform {
     Windows,
 }
 
+impl Platform {
+    /// Returns every platform that releases are built for.
+    pub fn all() -> impl Iterator<Item = Platform> {
+        [
+            Platform::LinuxMusl,
+            Platform::LinuxMuslAarch64,
+            Platform::LinuxMuslArm,
+            Platform::LinuxMuslArmV7,
+            Platform::MacOs,
+            Platform::MacOsAarch64,
+            Platform::Windows,
+        ]
+        .into_iter()
+    }
+}
+
 impl fmt::Display for Platform {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -102,10 +207,66 @@ impl fmt::Display for Platform {
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+impl FromStr for Platform {
+    type Err = Error;
+
+    /// Parses the target triple of a platform, as produced by its `Display` implementation.
+    fn from_str(s: &str) -> Result<Self> {
+        Platform::all()
+            .find(|platform| platform.to_string() == s)
+            .ok_or_else(|| Error::InvalidPlatform(s.to_string()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum ArchiveType {
     TarGz,
     Zip,
+    /// A tarball compressed with xz, which can only be extracted with the `tar-xz` feature.
+    TarXz,
+    /// A tarball compressed with Zstandard, which can only be extracted with the `tar-zst`
+    /// feature.
+    TarZst,
+    /// A tarball compressed with bzip2, which can only be extracted with the `tar-bz2` feature.
+    TarBz2,
+}
+
+impl ArchiveType {
+    /// Returns every type of archive that releases are packaged in.
+    pub fn all() -> impl Iterator<Item = ArchiveType> {
+        [
+            ArchiveType::TarGz,
+            ArchiveType::Zip,
+            ArchiveType::TarXz,
+            ArchiveType::TarZst,
+            ArchiveType::TarBz2,
+        ]
+        .into_iter()
+    }
+
+    /// Determines the type of an archive from the extension of its file name, e.g.,
+    /// `antnode-0.112.7-x86_64-unknown-linux-musl.tar.zst`.
+    ///
+    /// Only the full extension is recognised, so a file ending in `.gz` or `.zst` alone isn't
+    /// treated as a tarball.
+    pub fn from_file_name(file_name: &str) -> Option<ArchiveType> {
+        ArchiveType::all().find(|archive_type| {
+            file_name
+                .strip_suffix(&archive_type.to_string())
+                .is_some_and(|stem| stem.ends_with('.') && stem.len() > 1)
+        })
+    }
+
+    /// Returns whether archives of this type can be extracted with the features the crate was
+    /// built with.
+    pub fn is_extraction_enabled(&self) -> bool {
+        match self {
+            ArchiveType::TarGz | ArchiveType::Zip => true,
+            ArchiveType::TarXz => cfg!(feature = "tar-xz"),
+            ArchiveType::TarZst => cfg!(feature = "tar-zst"),
+            ArchiveType::TarBz2 => cfg!(feature = "tar-bz2"),
+        }
+    }
 }
 
 impl fmt::Display for ArchiveType {
@@ -113,15 +274,139 @@ impl fmt::Display for ArchiveType {
         match self {
             ArchiveType::TarGz => write!(f, "tar.gz"),
             ArchiveType::Zip => write!(f, "zip"),
+            ArchiveType::TarXz => write!(f, "tar.xz"),
+            ArchiveType::TarZst => write!(f, "tar.zst"),
+            ArchiveType::TarBz2 => write!(f, "tar.bz2"),
         }
     }
 }
 
-pub type ProgressCallback = dyn Fn(u64, u64) + Send + Sync;
+impl FromStr for ArchiveType {
+    type Err = Error;
+
+    /// Parses an archive file extension, as produced by the `Display` implementation.
+    fn from_str(s: &str) -> Result<Self> {
+        ArchiveType::all()
+            .find(|archive_type| archive_type.to_string() == s)
+            .ok_or_else(|| Error::InvalidArchiveType(s.to_string()))
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` using the `Display` and `FromStr` implementations, so
+/// the serialized form is the same string used in archive names.
+#[cfg(feature = "serde")]
+macro_rules! impl_serde_from_str {
+    ($($t:ty),*) => {
+        $(
+            impl serde::Serialize for $t {
+                fn serialize<S: serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> std::result::Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for $t {
+                fn deserialize<D: serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> std::result::Result<Self, D::Error> {
+                    let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                    s.parse().map_err(serde::de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "serde")]
+impl_serde_from_str!(ReleaseType, Platform, ArchiveType);
+
+/// A release channel, which determines how far ahead of the stable releases a consumer is willing
+/// to track.
+///
+/// Each channel also accepts the releases of the channels that are more stable than it.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Channel {
+    /// Only stable releases.
+    Stable,
+    /// Release candidates, with a pre-release identifier such as `rc.1`, and stable releases.
+    ReleaseCandidate,
+    /// Alpha and beta pre-releases, release candidates and stable releases.
+    Alpha,
+    /// Any release, whatever its pre-release identifier.
+    Any,
+}
+
+impl Channel {
+    /// Returns whether `version` is published on this channel.
+    pub fn accepts(&self, version: &Version) -> bool {
+        if version.pre.is_empty() {
+            return true;
+        }
+        let pre = version.pre.as_str();
+        match self {
+            Channel::Stable => false,
+            Channel::ReleaseCandidate => pre.starts_with("rc"),
+            Channel::Alpha => {
+                pre.starts_with("rc") || pre.starts_with("beta") || pre.starts_with("alpha")
+            }
+            Channel::Any => true,
+        }
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Stable => write!(f, "stable"),
+            Channel::ReleaseCandidate => write!(f, "rc"),
+            Channel::Alpha => write!(f, "alpha"),
+            Channel::Any => write!(f, "any"),
+        }
+    }
+}
+
+/// A published version of a release.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleaseInfo {
+    pub version: Version,
+    pub published_at: DateTime<Utc>,
+    pub yanked: bool,
+}
+
+/// Where the available versions of a release are discovered from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum VersionSource {
+    /// The crates.io API, for release types that are published as crates.
+    CratesIo,
+    /// A listing of the release type's S3 bucket, with versions parsed from the archive names.
+    S3Listing,
+    /// The releases of the GitHub repository, with versions parsed from the tag names.
+    GitHubReleases,
+}
+
+/// Receives the progress of downloads as `ProgressEvent`s. See `ProgressHandler` for what can be
+/// used as one.
+pub type ProgressCallback = dyn ProgressHandler;
 
 #[async_trait]
 pub trait AntReleaseRepoActions {
     async fn get_latest_version(&self, release_type: &ReleaseType) -> Result<Version>;
+    async fn get_latest_version_for_channel(
+        &self,
+        release_type: &ReleaseType,
+        channel: &Channel,
+    ) -> Result<Version>;
+    async fn list_versions(&self, release_type: &ReleaseType) -> Result<Vec<ReleaseInfo>>;
+    async fn get_s3_release_index(&self, release_type: &ReleaseType) -> Result<S3ReleaseIndex>;
+    async fn list_github_releases(&self, release_type: &ReleaseType) -> Result<Vec<GitHubRelease>>;
+    async fn resolve_version(
+        &self,
+        release_type: &ReleaseType,
+        version_req: &VersionReq,
+        include_prerelease: bool,
+    ) -> Result<Version>;
     async fn download_release_from_s3(
         &self,
         release_type: &ReleaseType,
@@ -131,28 +416,62 @@ pub trait AntReleaseRepoActions {
         dest_path: &Path,
         callback: &ProgressCallback,
     ) -> Result<PathBuf>;
+    async fn download_release_from_github(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf>;
     async fn download_release(
         &self,
         url: &str,
         dest_dir_path: &Path,
         callback: &ProgressCallback,
     ) -> Result<PathBuf>;
+    async fn download_release_with_checksum(
+        &self,
+        url: &str,
+        expected_sha256: &str,
+        dest_dir_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf>;
     async fn download_winsw(&self, dest_path: &Path, callback: &ProgressCallback) -> Result<()>;
+    async fn download_and_extract(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_dir_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf>;
+    async fn download_releases(
+        &self,
+        targets: &[DownloadTarget],
+        dest_dir_path: &Path,
+        callback: &BatchProgressCallback,
+    ) -> Vec<BatchDownloadResult>;
     fn extract_release_archive(&self, archive_path: &Path, dest_dir_path: &Path)
         -> Result<PathBuf>;
+    fn extract_all_from_archive(
+        &self,
+        archive_path: &Path,
+        dest_dir_path: &Path,
+    ) -> Result<ExtractionManifest>;
+    fn extract_binary_from_archive(
+        &self,
+        archive_path: &Path,
+        binary_name: &str,
+        dest_dir_path: &Path,
+    ) -> Result<PathBuf>;
 }
 
 impl dyn AntReleaseRepoActions {
     pub fn default_config() -> Box<dyn AntReleaseRepoActions> {
-        Box::new(AntReleaseRepository {
-            github_api_base_url: GITHUB_API_URL.to_string(),
-            nat_detection_base_url: NAT_DETECTION_S3_BASE_URL.to_string(),
-            node_launchpad_base_url: NODE_LAUNCHPAD_S3_BASE_URL.to_string(),
-            ant_base_url: ANT_S3_BASE_URL.to_string(),
-            antnode_base_url: ANTNODE_S3_BASE_URL.to_string(),
-            antctl_base_url: ANTCTL_S3_BASE_URL.to_string(),
-            antnode_rpc_client_base_url: ANTNODE_RPC_CLIENT_S3_BASE_URL.to_string(),
-        })
+        Box::new(AntReleaseRepository::default())
     }
 }
 
@@ -161,12 +480,125 @@ pub struct AntReleaseRepository {
     pub antctl_base_url: String,
     pub antnode_base_url: String,
     pub antnode_rpc_client_base_url: String,
+    /// How the permissions of extracted release binaries are set on Unix.
+    pub binary_permissions: BinaryPermissions,
+    /// When set, archives downloaded from S3 are kept in this cache and reused by later requests
+    /// for the same release.
+    pub cache: Option<ReleaseCache>,
+    pub crates_io_api_base_url: String,
     pub github_api_base_url: String,
+    /// The GitHub repository releases are published to, in `owner/name` form.
+    pub github_repository: String,
+    /// A token to authenticate requests to the GitHub API with, which raises the rate limit.
+    pub github_token: Option<String>,
+    /// The client every request is made with, so connections are reused across calls. It's built
+    /// from the default `HttpConfig`, and can be replaced with one built from a custom config by
+    /// `HttpConfig::build_client`.
+    pub http_client: Client,
+    /// The most downloads `download_releases` runs at the same time.
+    pub max_concurrent_downloads: usize,
+    /// How long a mirror is tried only as a last resort after it fails.
+    pub mirror_cool_down: Duration,
+    /// The mirrors that have failed recently. Repositories that share it avoid the same mirrors.
+    pub mirror_health: MirrorHealth,
+    /// Further base URLs to fall over to, in order, when downloading from the base URL of the
+    /// release type fails with a connection error, including one partway through the download, a
+    /// timeout or a server error. Archives from a mirror are verified in the same way as those
+    /// from the base URL.
+    pub mirrors: HashMap<ReleaseType, Vec<String>>,
     pub nat_detection_base_url: String,
     pub node_launchpad_base_url: String,
+    pub checksum_verification: ChecksumVerification,
+    pub signature_verification: SignatureVerification,
+    /// Overrides where versions of particular release types are discovered from. Release types
+    /// without an entry use crates.io.
+    pub version_sources: HashMap<ReleaseType, VersionSource>,
+    pub retry_policy: RetryPolicy,
+    /// Invoked each time a failed network operation is about to be retried.
+    pub on_retry: Option<Arc<RetryCallback>>,
+    /// Where the WinSW service wrapper is downloaded from.
+    pub winsw_url: String,
+}
+
+impl Default for AntReleaseRepository {
+    fn default() -> Self {
+        Self {
+            github_api_base_url: GITHUB_API_URL.to_string(),
+            github_repository: github::DEFAULT_GITHUB_REPOSITORY.to_string(),
+            github_token: None,
+            // Like `Client::new`, this only fails if the TLS backend can't be initialised.
+            http_client: HttpConfig::default()
+                .build_client()
+                .expect("the default HTTP client should build"),
+            max_concurrent_downloads: batch::DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            mirror_cool_down: mirror::DEFAULT_MIRROR_COOL_DOWN,
+            mirror_health: MirrorHealth::default(),
+            mirrors: HashMap::new(),
+            nat_detection_base_url: NAT_DETECTION_S3_BASE_URL.to_string(),
+            node_launchpad_base_url: NODE_LAUNCHPAD_S3_BASE_URL.to_string(),
+            ant_base_url: ANT_S3_BASE_URL.to_string(),
+            antnode_base_url: ANTNODE_S3_BASE_URL.to_string(),
+            antctl_base_url: ANTCTL_S3_BASE_URL.to_string(),
+            antnode_rpc_client_base_url: ANTNODE_RPC_CLIENT_S3_BASE_URL.to_string(),
+            binary_permissions: BinaryPermissions::default(),
+            checksum_verification: ChecksumVerification::default(),
+            signature_verification: SignatureVerification::default(),
+            // The node launchpad is not published to crates.io, so its releases can only be
+            // discovered from the bucket they are uploaded to.
+            version_sources: HashMap::from([(
+                ReleaseType::NodeLaunchpad,
+                VersionSource::S3Listing,
+            )]),
+            cache: None,
+            crates_io_api_base_url: CRATES_IO_API_URL.to_string(),
+            retry_policy: RetryPolicy::default(),
+            on_retry: None,
+            winsw_url: WINSW_URL.to_string(),
+        }
+    }
 }
 
 impl AntReleaseRepository {
+    /// Returns a builder that starts from the default configuration.
+    pub fn builder() -> AntReleaseRepositoryBuilder {
+        AntReleaseRepositoryBuilder::new()
+    }
+
+    /// Creates a repository from the defaults, overridden by any `ANT_RELEASES_*` environment
+    /// variables. See `RepositoryConfig` for the variables that are recognised.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a variable has an invalid value, naming the
+    /// variable, or if the HTTP client can't be built.
+    pub fn from_env() -> Result<Self> {
+        RepositoryConfig::from_env()?.apply(Self::builder()).build()
+    }
+
+    /// Creates a repository from a TOML config file. See `RepositoryConfig` for its format.
+    ///
+    /// Settings are taken from, in increasing order of precedence:
+    /// 1. The defaults
+    /// 2. The config file
+    /// 3. `ANT_RELEASES_*` environment variables
+    ///
+    /// So an environment variable can override a single setting from a file that's shared
+    /// between hosts.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The file can't be read or parsed
+    /// - A setting in the file or the environment has an invalid value, in which case the error
+    ///   names the key or variable
+    /// - The HTTP client can't be built
+    pub fn from_config_file(path: &Path) -> Result<Self> {
+        RepositoryConfig::from_file(path)?
+            .merge(RepositoryConfig::from_env()?)
+            .apply(Self::builder())
+            .build()
+    }
+
     fn get_base_url(&self, release_type: &ReleaseType) -> String {
         match release_type {
             ReleaseType::Ant => self.ant_base_url.clone(),
@@ -179,163 +611,1408 @@ impl AntReleaseRepository {
         }
     }
 
-    async fn download_url(
-        &self,
-        url: &str,
-        dest_path: &Path,
-        callback: &ProgressCallback,
-    ) -> Result<()> {
-        let client = Client::new();
-        let mut response = client.get(url).send().await?;
-        if !response.status().is_success() {
-            return Err(Error::ReleaseBinaryNotFound(url.to_string()));
+    /// Returns the base URL of the release type followed by its mirrors, in the order they should
+    /// be tried.
+    fn get_base_urls(&self, release_type: &ReleaseType) -> Vec<String> {
+        let mut base_urls = vec![self.get_base_url(release_type)];
+        if let Some(mirrors) = self.mirrors.get(release_type) {
+            base_urls.extend(mirrors.iter().cloned());
         }
+        self.mirror_health.order(base_urls, self.mirror_cool_down)
+    }
 
-        let total_size = response
-            .headers()
-            .get("content-length")
-            .and_then(|ct_len| ct_len.to_str().ok())
-            .and_then(|ct_len| ct_len.parse::<u64>().ok())
-            .unwrap_or(0);
-
-        let mut downloaded: u64 = 0;
-        let mut out_file = File::create(&dest_path).await?;
-
-        while let Some(chunk) = response.chunk().await.unwrap() {
-            downloaded += chunk.len() as u64;
-            out_file.write_all(&chunk).await?;
-            callback(downloaded, total_size);
+    /// Runs `operation` with each base URL of the release type in turn, until it succeeds or fails
+    /// with an error that another mirror wouldn't fix.
+    ///
+    /// Mirrors that fail are recorded in `mirror_health`, so that later operations try them last
+    /// until the cool-down has passed. If every mirror fails, the error from the last is returned.
+    async fn with_mirrors<T, F, Fut>(
+        &self,
+        release_type: &ReleaseType,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_failure = None;
+        for base_url in self.get_base_urls(release_type) {
+            match operation(base_url.clone()).await {
+                Err(err) if mirror::is_mirror_failure(&err) => {
+                    self.mirror_health.record_failure(&base_url);
+                    last_failure = Some(err);
+                }
+                result => {
+                    if result.is_ok() {
+                        self.mirror_health.record_success(&base_url);
+                    }
+                    return result;
+                }
+            }
         }
+        Err(last_failure.expect("the base URL of the release type is always tried"))
+    }
 
-        Ok(())
+    /// Describes the verification an archive of the release type passes before it's cached. The
+    /// trusted keys are identified by a digest, rather than listed.
+    fn get_verification_policy(&self, release_type: &ReleaseType) -> String {
+        let checksum = match self.checksum_verification {
+            ChecksumVerification::Disabled => "disabled",
+            ChecksumVerification::IfAvailable => "if-available",
+            ChecksumVerification::Required => "required",
+        };
+        let signature_verification = &self.signature_verification;
+        let signature = if signature_verification.is_enabled_for(release_type) {
+            let mut keys: Vec<&str> = signature_verification
+                .trusted_public_keys
+                .iter()
+                .map(|key| key.trim())
+                .collect();
+            keys.sort_unstable();
+            let keys_digest = checksum::to_hex(Sha256::new_with_prefix(keys.join("\n")));
+            let requirement = if signature_verification.is_required_for(release_type) {
+                "required"
+            } else {
+                "if-available"
+            };
+            format!("{requirement}:{keys_digest}")
+        } else {
+            "disabled".to_string()
+        };
+        format!("checksum={checksum};signature={signature}")
     }
-}
 
-#[async_trait]
-impl AntReleaseRepoActions for AntReleaseRepository {
-    /// Uses the crates.io API to obtain the latest version of a crate.
-    ///
-    /// # Arguments
-    ///
-    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `Result` containing a `String` with the latest version number in the semantic format.
-    /// Otherwise, returns an `Error`.
-    ///
-    /// # Errors
-    ///
-    /// This function will return an error if:
-    /// - The HTTP request to crates.io API fails
-    /// - The received JSON data does not have a `crate.newest_version` value
-    async fn get_latest_version(&self, release_type: &ReleaseType) -> Result<Version> {
+    fn get_version_source(&self, release_type: &ReleaseType) -> VersionSource {
+        self.version_sources
+            .get(release_type)
+            .copied()
+            .unwrap_or(VersionSource::CratesIo)
+    }
+
+    fn get_crate_name(&self, release_type: &ReleaseType) -> Result<&'static str> {
         // For the time being, the node launchpad needs to be treated as a special case, because it
         // cannot be published.
         if matches!(release_type, ReleaseType::NodeLaunchpad) {
-            return Ok(Version::parse("0.1.0")?);
+            return Err(Error::ReleaseNotPublishedToCratesIo(
+                release_type.to_string(),
+            ));
         }
+        Ok(*RELEASE_TYPE_CRATE_NAME_MAP.get(release_type).unwrap())
+    }
+
+    async fn list_versions_from_crates_io(
+        &self,
+        release_type: &ReleaseType,
+    ) -> Result<Vec<ReleaseInfo>> {
+        let crate_name = self.get_crate_name(release_type)?;
+        let versions_url = format!(
+            "{}/crates/{}/versions",
+            self.crates_io_api_base_url, crate_name
+        );
 
-        let crate_name = *RELEASE_TYPE_CRATE_NAME_MAP.get(release_type).unwrap();
-        let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
+        let mut releases = Vec::new();
+        let mut url = format!("{versions_url}?per_page=100");
+        loop {
+            let json = self.get_crates_io_json(&url).await?;
+            let versions = json["versions"]
+                .as_array()
+                .ok_or_else(|| Error::LatestReleaseNotFound(release_type.to_string()))?;
+            for version in versions {
+                let (Some(num), Some(created_at)) =
+                    (version["num"].as_str(), version["created_at"].as_str())
+                else {
+                    continue;
+                };
+                releases.push(ReleaseInfo {
+                    version: Version::parse(num)?,
+                    published_at: DateTime::parse_from_rfc3339(created_at)?.with_timezone(&Utc),
+                    yanked: version["yanked"].as_bool().unwrap_or(false),
+                });
+            }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .get(url)
-            .header("User-Agent", "reqwest")
-            .send()
-            .await?;
-        if !response.status().is_success() {
-            return Err(Error::CratesIoResponseError(response.status().as_u16()));
+            // The API returns the query string for the next page, or null on the last one.
+            match json["meta"]["next_page"].as_str() {
+                Some(next_page) if !versions.is_empty() => {
+                    url = format!("{versions_url}{next_page}");
+                }
+                _ => break,
+            }
         }
+        Ok(releases)
+    }
 
-        let body = response.text().await?;
-        let json: Value = serde_json::from_str(&body)?;
+    /// Discovers the versions of a release from the archives in its S3 bucket.
+    ///
+    /// A version is considered to have been published when its first archive was uploaded.
+    async fn list_versions_from_s3(&self, release_type: &ReleaseType) -> Result<Vec<ReleaseInfo>> {
+        let index = self.get_s3_release_index(release_type).await?;
+        Ok(index
+            .published_dates()
+            .into_iter()
+            .map(|(version, published_at)| ReleaseInfo {
+                version,
+                published_at,
+                yanked: false,
+            })
+            .collect())
+    }
 
-        if let Some(version) = json["crate"]["newest_version"].as_str() {
-            return Ok(Version::parse(version)?);
+    /// Lists the objects in an S3 bucket whose keys start with `prefix`, following the
+    /// continuation tokens of a `ListObjectsV2` listing until every page has been retrieved.
+    async fn list_s3_objects(&self, bucket_url: &str, prefix: &str) -> Result<Vec<S3Object>> {
+        let client = &self.http_client;
+        let mut objects = Vec::new();
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let token = continuation_token.as_deref();
+            let body = self
+                .retry_policy
+                .run(self.on_retry.as_deref(), move || async move {
+                    let mut query = vec![("list-type", "2"), ("prefix", prefix)];
+                    if let Some(token) = token {
+                        query.push(("continuation-token", token));
+                    }
+                    let response = client.get(bucket_url).query(&query).send().await?;
+                    if !response.status().is_success() {
+                        return Err(Error::UnexpectedResponseStatus(
+                            response.status().as_u16(),
+                            bucket_url.to_string(),
+                        ));
+                    }
+                    Ok(response.text().await?)
+                })
+                .await?;
+
+            let page = s3::parse_list_objects_response(&body)?;
+            objects.extend(page.objects);
+            match page.next_continuation_token {
+                Some(token) => continuation_token = Some(token),
+                None => break,
+            }
         }
+        Ok(objects)
+    }
 
-        Err(Error::LatestReleaseNotFound(release_type.to_string()))
+    /// Discovers the versions of a release from the tags of the GitHub releases.
+    async fn list_versions_from_github(
+        &self,
+        release_type: &ReleaseType,
+    ) -> Result<Vec<ReleaseInfo>> {
+        Ok(self
+            .list_github_releases(release_type)
+            .await?
+            .into_iter()
+            .map(|release| ReleaseInfo {
+                version: release.version,
+                published_at: release.published_at,
+                yanked: false,
+            })
+            .collect())
     }
 
-    /// Downloads a release binary archive from S3.
+    /// Makes a request to the GitHub API and parses the JSON response.
     ///
-    /// # Arguments
+    /// Returns the URL of the next page along with the response, if it's paginated.
+    async fn get_github_json(&self, url: &str) -> Result<(Value, Option<String>)> {
+        let client = &self.http_client;
+        self.retry_policy
+            .run(self.on_retry.as_deref(), move || async move {
+                let mut request = client
+                    .get(url)
+                    .header("Accept", "application/vnd.github+json");
+                if let Some(token) = &self.github_token {
+                    request = request.bearer_auth(token);
+                }
+                let response = request.send().await?;
+                if let Some(err) =
+                    github::get_rate_limit_error(response.status(), response.headers())
+                {
+                    return Err(err);
+                }
+                if !response.status().is_success() {
+                    return Err(Error::UnexpectedResponseStatus(
+                        response.status().as_u16(),
+                        url.to_string(),
+                    ));
+                }
+                let next_page_url = github::get_next_page_url(response.headers())?;
+                let json: Value = serde_json::from_str(&response.text().await?)?;
+                Ok((json, next_page_url))
+            })
+            .await
+    }
+
+    /// Makes a request to the crates.io API and parses the JSON response.
+    async fn get_crates_io_json(&self, url: &str) -> Result<Value> {
+        let client = &self.http_client;
+        let body = self
+            .retry_policy
+            .run(self.on_retry.as_deref(), move || async move {
+                let response = client.get(url).send().await?;
+                if !response.status().is_success() {
+                    return Err(Error::CratesIoResponseError(response.status().as_u16()));
+                }
+                Ok(response.text().await?)
+            })
+            .await?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Downloads the resource at `url` to `dest_path`.
     ///
-    /// - `release_type`: The type of release.
-    /// - `version`: The version of the release.
-    /// - `platform`: The target platform.
-    /// - `archive_type`: The type of archive (e.g., tar.gz, zip).
-    /// - `dest_path`: The directory where the downloaded archive will be stored.
-    /// - `callback`: A callback function that can be used for download progress.
+    /// The data is first written to a `.part` file alongside the destination, which is renamed
+    /// into place once the download completes. If a `.part` file is already present, for example
+    /// from a previous attempt that was interrupted, a `Range` request is used to fetch only the
+    /// remaining bytes. Servers that ignore the range and reply with the full content cause the
+    /// partial file to be discarded and the download to start from the beginning.
     ///
-    /// # Returns
+    /// The `ETag` or `Last-Modified` value the download started with is kept in a `.validator`
+    /// file next to the `.part` file and sent as `If-Range`, so that if the resource has changed
+    /// since, the server replies with the new content in full rather than splicing it onto the
+    /// old. A partial file without a validator is downloaded again from the beginning.
     ///
-    /// A `Result` with `PathBuf` indicating the full path of the downloaded archive, or an error if
-    /// the download or file write operation fails.
-    async fn download_release_from_s3(
+    /// Transient failures are retried according to the repository's retry policy, with each retry
+    /// resuming from wherever the previous attempt got to.
+    ///
+    /// If `expected_sha256` is provided, the digest is computed while the data is streamed and
+    /// compared once the download completes. On a mismatch the downloaded file is deleted.
+    async fn download_url(
         &self,
-        release_type: &ReleaseType,
-        version: &Version,
-        platform: &Platform,
-        archive_type: &ArchiveType,
+        url: &str,
         dest_path: &Path,
-        callback: &ProgressCallback,
-    ) -> Result<PathBuf> {
-        let archive_ext = archive_type.to_string();
-        let url = format!(
-            "{}/{}-{}-{}.{}",
-            self.get_base_url(release_type),
-            release_type.to_string().to_lowercase(),
-            version,
-            platform,
-            archive_type
-        );
-
-        let archive_name = format!(
-            "{}-{}-{}.{}",
-            release_type.to_string().to_lowercase(),
-            version,
-            platform,
-            archive_ext
-        );
-        let archive_path = dest_path.join(archive_name);
-
-        self.download_url(&url, &archive_path, callback).await?;
+        expected_sha256: Option<&str>,
+        callback: &dyn ProgressHandler,
+    ) -> Result<()> {
+        self.run_with_retries(callback, move || {
+            self.try_download_url(url, dest_path, expected_sha256, callback)
+        })
+        .await
+    }
 
-        Ok(archive_path)
+    /// Runs a network operation according to the retry policy, reporting each retry to `callback`
+    /// as well as to `on_retry`.
+    async fn run_with_retries<T, F, Fut>(
+        &self,
+        callback: &dyn ProgressHandler,
+        operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let on_retry = |attempt: u32, err: &Error| {
+            if let Some(on_retry) = &self.on_retry {
+                on_retry(attempt, err);
+            }
+            callback.on_progress(ProgressEvent::Retrying {
+                attempt,
+                reason: err.to_string(),
+            });
+        };
+        self.retry_policy.run(Some(&on_retry), operation).await
     }
 
-    async fn download_release(
+    async fn try_download_url(
         &self,
         url: &str,
-        dest_dir_path: &Path,
-        callback: &ProgressCallback,
-    ) -> Result<PathBuf> {
-        if !url.ends_with(".tar.gz") && !url.ends_with(".zip") {
-            return Err(Error::UrlIsNotArchive);
+        dest_path: &Path,
+        expected_sha256: Option<&str>,
+        callback: &dyn ProgressHandler,
+    ) -> Result<()> {
+        let part_path = get_part_path(dest_path);
+        let validator_path = get_validator_path(dest_path);
+        let validator = tokio::fs::read_to_string(&validator_path).await.ok();
+        let mut downloaded = match (tokio::fs::metadata(&part_path).await, &validator) {
+            (Ok(metadata), Some(_)) => metadata.len(),
+            _ => 0,
+        };
+
+        callback.on_progress(ProgressEvent::Connecting);
+        let mut response =
+            send_download_request(&self.http_client, url, downloaded, validator.as_deref()).await?;
+        if downloaded > 0 {
+            let resumable = response.status() == StatusCode::PARTIAL_CONTENT
+                && get_content_range_start(&response) == Some(downloaded);
+            if !resumable && response.status() != StatusCode::OK {
+                // The partial file can't be used to resume, either because the server rejected
+                // the range or returned a range we didn't ask for, so request everything again.
+                downloaded = 0;
+                response = send_download_request(&self.http_client, url, downloaded, None).await?;
+            }
+        }
+        if !response.status().is_success() {
+            return Err(get_download_status_error(response.status(), url));
         }
 
-        let file_name = url
-            .split('/')
-            .last()
-            .ok_or_else(|| Error::CannotParseFilenameFromUrl)?;
-        let dest_path = dest_dir_path.join(file_name);
+        let mut hasher = Sha256::new();
+        let mut out_file = if response.status() == StatusCode::PARTIAL_CONTENT {
+            if expected_sha256.is_some() {
+                checksum::update_from_file(&mut hasher, &part_path).await?;
+            }
+            OpenOptions::new().append(true).open(&part_path).await?
+        } else {
+            downloaded = 0;
+            // The validator is recorded before any data, so an interrupted download can resume.
+            match get_validator(&response) {
+                Some(validator) => tokio::fs::write(&validator_path, validator).await?,
+                None => remove_file_if_exists(&validator_path).await?,
+            }
+            File::create(&part_path).await?
+        };
 
-        self.download_url(url, &dest_path, callback).await?;
+        let total = if response.status() == StatusCode::PARTIAL_CONTENT {
+            get_content_range_total(&response)
+        } else {
+            get_content_length(&response)
+        };
 
-        Ok(dest_path)
-    }
+        callback.on_progress(ProgressEvent::Downloading { downloaded, total });
+        while let Some(chunk) = response.chunk().await? {
+            downloaded += chunk.len() as u64;
+            out_file.write_all(&chunk).await?;
+            if expected_sha256.is_some() {
+                hasher.update(&chunk);
+            }
+            callback.on_progress(ProgressEvent::Downloading { downloaded, total });
+        }
+        out_file.flush().await?;
+        drop(out_file);
 
-    async fn download_winsw(&self, dest_path: &Path, callback: &ProgressCallback) -> Result<()> {
-        self.download_url(WINSW_URL, dest_path, callback).await?;
-        Ok(())
-    }
+        if let Some(expected) = expected_sha256 {
+            callback.on_progress(ProgressEvent::Verifying);
+            let actual = checksum::to_hex(hasher);
+            if !actual.eq_ignore_ascii_case(expected) {
+                tokio::fs::remove_file(&part_path).await?;
+                remove_file_if_exists(&validator_path).await?;
+                return Err(Error::ChecksumMismatch {
+                    expected: expected.to_lowercase(),
+                    actual,
+                });
+            }
+        }
+
+        tokio::fs::rename(&part_path, dest_path).await?;
+        remove_file_if_exists(&validator_path).await?;
+
+        Ok(())
+    }
+
+    /// Makes a single attempt to download a tarball and extract the release binary from it as the
+    /// data arrives, returning the path the binary was extracted to.
+    ///
+    /// The extraction runs on a blocking thread, which is fed the chunks of the response through
+    /// a channel. The checksum and signature are computed over the compressed data as it is
+    /// received, and are only checked once all of it has been read.
+    #[allow(clippy::too_many_arguments)]
+    async fn try_download_and_extract_tarball(
+        &self,
+        url: &str,
+        artifact: &ReleaseArtifact,
+        binary_name: &str,
+        staging_dir_path: &Path,
+        expected_sha256: Option<&str>,
+        signature: Option<&str>,
+        callback: &dyn ProgressHandler,
+    ) -> Result<PathBuf> {
+        let archive_name = artifact.to_file_name();
+        let public_keys = self.signature_verification.parse_public_keys()?;
+        let signature = signature
+            .map(|signature| signature::decode_signature(signature, &archive_name))
+            .transpose()?;
+        let mut verifier = signature
+            .as_ref()
+            .map(|signature| signature::get_stream_verifier(&public_keys, signature, &archive_name))
+            .transpose()?;
+
+        callback.on_progress(ProgressEvent::Connecting);
+        let mut response = send_download_request(&self.http_client, url, 0, None).await?;
+        if !response.status().is_success() {
+            return Err(get_download_status_error(response.status(), url));
+        }
+        let total = get_content_length(&response);
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(16);
+        // The extraction can't report progress itself, so it signals when it reaches the binary
+        // and `Extracting` is reported from here.
+        let (binary_reached_sender, mut binary_reached) = tokio::sync::oneshot::channel();
+        let extraction = tokio::task::spawn_blocking({
+            let archive_type = artifact.archive_type;
+            let binary_name = binary_name.to_string();
+            let staging_dir_path = staging_dir_path.to_path_buf();
+            let permissions = self.binary_permissions;
+            move || -> Result<PathBuf> {
+                let mut reader = ChunkReader::new(receiver);
+                let binary_path = extract::extract_binary_from_tarball(
+                    archive_type,
+                    &mut reader,
+                    &binary_name,
+                    &staging_dir_path,
+                    permissions,
+                    || {
+                        let _ = binary_reached_sender.send(());
+                    },
+                )?;
+                // The rest of the archive still has to be downloaded to verify it.
+                std::io::copy(&mut reader, &mut std::io::sink())?;
+                Ok(binary_path)
+            }
+        });
+
+        let mut hasher = Sha256::new();
+        let mut downloaded = 0;
+        let mut reported_extracting = false;
+        let mut report_extracting_once_reached = || {
+            if !reported_extracting && binary_reached.try_recv().is_ok() {
+                reported_extracting = true;
+                callback.on_progress(ProgressEvent::Extracting);
+            }
+        };
+        callback.on_progress(ProgressEvent::Downloading { downloaded, total });
+        let streamed: Result<()> = async {
+            while let Some(chunk) = response.chunk().await? {
+                report_extracting_once_reached();
+                downloaded += chunk.len() as u64;
+                hasher.update(&chunk);
+                if let Some(verifier) = verifier.as_mut() {
+                    verifier.update(&chunk);
+                }
+                callback.on_progress(ProgressEvent::Downloading { downloaded, total });
+                if sender.send(chunk).await.is_err() {
+                    // The extraction has failed, and its error is returned below.
+                    break;
+                }
+            }
+            Ok(())
+        }
+        .await;
+        drop(sender);
+
+        // Wait for the extraction to finish even if the download failed, so nothing is still
+        // writing to the staging directory when it's removed.
+        let extracted = extraction
+            .await
+            .map_err(|err| Error::Io(std::io::Error::other(err)))?;
+        streamed?;
+        let binary_path = extracted?;
+        // The binary may only have been reached after the last chunk was received.
+        report_extracting_once_reached();
+
+        if expected_sha256.is_some() || verifier.is_some() {
+            callback.on_progress(ProgressEvent::Verifying);
+        }
+        if let Some(expected) = expected_sha256 {
+            let actual = checksum::to_hex(hasher);
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(Error::ChecksumMismatch {
+                    expected: expected.to_lowercase(),
+                    actual,
+                });
+            }
+        }
+        if let (Some(verifier), Some(signature)) = (verifier, &signature) {
+            signature::finalize_stream_verifier(verifier, signature, &archive_name, &archive_name)?;
+        }
+
+        Ok(binary_path)
+    }
+
+    /// Downloads a release archive from S3, as described for `download_release_from_s3`, without
+    /// reporting the outcome to `callback`.
+    async fn download_archive_from_s3(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_path: &Path,
+        callback: &dyn ProgressHandler,
+    ) -> Result<PathBuf> {
+        let artifact =
+            ReleaseArtifact::new(*release_type, version.clone(), *platform, *archive_type);
+        let archive_path = dest_path.join(artifact.to_file_name());
+
+        let verified_with = self.get_verification_policy(release_type);
+        if let Some(cache) = &self.cache {
+            if let Some(entry) = cache.get(release_type, version, platform, archive_type)? {
+                // An entry that passed different verification to what's configured now, e.g.,
+                // because it was cached before any keys were trusted, is downloaded again. The
+                // entry could also be evicted by another process before it's copied. Either way,
+                // it's treated as a cache miss.
+                if entry.verified_with == verified_with
+                    && cache.copy_to(&entry, &archive_path).is_ok()
+                {
+                    callback.on_progress(ProgressEvent::Downloading {
+                        downloaded: entry.size,
+                        total: Some(entry.size),
+                    });
+                    return Ok(archive_path);
+                }
+            }
+        }
+
+        // The checksum and signature are fetched from the same mirror as the archive, so a
+        // mirror serving a different archive can't pass verification.
+        let signature = self
+            .with_mirrors(release_type, |base_url| {
+                let url = artifact.to_url(&base_url);
+                let archive_path = &archive_path;
+                async move {
+                    let expected_sha256 = self.fetch_published_checksum(&url).await?;
+                    let signature = self.fetch_published_signature(release_type, &url).await?;
+                    self.download_url(&url, archive_path, expected_sha256.as_deref(), callback)
+                        .await?;
+                    Ok(signature)
+                }
+            })
+            .await?;
+        self.verify_downloaded_signature(&archive_path, signature.as_deref(), callback)
+            .await?;
+
+        if let Some(cache) = &self.cache {
+            cache.insert(
+                release_type,
+                version,
+                platform,
+                archive_type,
+                &archive_path,
+                &verified_with,
+            )?;
+            cache.prune()?;
+        }
+
+        Ok(archive_path)
+    }
+
+    /// Downloads a release and extracts its binary, as described for `download_and_extract`,
+    /// without reporting the outcome to `callback`.
+    async fn download_and_extract_binary(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_dir_path: &Path,
+        callback: &dyn ProgressHandler,
+    ) -> Result<PathBuf> {
+        if !archive_type.is_extraction_enabled() {
+            return Err(Error::ArchiveTypeNotEnabled(*archive_type));
+        }
+
+        let artifact =
+            ReleaseArtifact::new(*release_type, version.clone(), *platform, *archive_type);
+        let binary_name = release_type.to_string();
+        // Everything is written to a hidden directory first, so that a binary which fails
+        // verification never appears in the destination.
+        let staging_dir_path = dest_dir_path.join(format!(".{}.part", artifact.to_file_name()));
+        tokio::fs::create_dir_all(&staging_dir_path).await?;
+
+        let staged = if *archive_type == ArchiveType::Zip || self.cache.is_some() {
+            match self
+                .download_archive_from_s3(
+                    release_type,
+                    version,
+                    platform,
+                    archive_type,
+                    &staging_dir_path,
+                    callback,
+                )
+                .await
+            {
+                Ok(archive_path) => {
+                    callback.on_progress(ProgressEvent::Extracting);
+                    self.extract_binary_from_archive(&archive_path, &binary_name, &staging_dir_path)
+                }
+                Err(err) => Err(err),
+            }
+        } else {
+            self.with_mirrors(release_type, |base_url| {
+                let url = artifact.to_url(&base_url);
+                let (artifact, binary_name, staging_dir_path) =
+                    (&artifact, &binary_name, &staging_dir_path);
+                async move {
+                    let expected_sha256 = self.fetch_published_checksum(&url).await?;
+                    let signature = self.fetch_published_signature(release_type, &url).await?;
+                    self.run_with_retries(callback, || {
+                        self.try_download_and_extract_tarball(
+                            &url,
+                            artifact,
+                            binary_name,
+                            staging_dir_path,
+                            expected_sha256.as_deref(),
+                            signature.as_deref(),
+                            callback,
+                        )
+                    })
+                    .await
+                }
+            })
+            .await
+        };
+
+        let result = match staged {
+            Ok(staged_path) => {
+                let binary_path = dest_dir_path.join(staged_path.file_name().unwrap_or_default());
+                tokio::fs::rename(&staged_path, &binary_path)
+                    .await
+                    .map(|_| binary_path)
+                    .map_err(Error::from)
+            }
+            Err(err) => Err(err),
+        };
+        tokio::fs::remove_dir_all(&staging_dir_path).await?;
+        result
+    }
+
+    /// Verifies a downloaded archive against its signature, if it has one, deleting the archive if
+    /// the signature isn't valid.
+    async fn verify_downloaded_signature(
+        &self,
+        archive_path: &Path,
+        signature: Option<&str>,
+        callback: &dyn ProgressHandler,
+    ) -> Result<()> {
+        let Some(signature) = signature else {
+            return Ok(());
+        };
+        callback.on_progress(ProgressEvent::Verifying);
+        if let Err(err) = self
+            .signature_verification
+            .verify_file(archive_path, signature)
+            .await
+        {
+            tokio::fs::remove_file(archive_path).await?;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Fetches the SHA-256 checksum published alongside the archive at `url`.
+    ///
+    /// Returns `None` if no checksum was published and verification isn't required.
+    async fn fetch_published_checksum(&self, url: &str) -> Result<Option<String>> {
+        if self.checksum_verification == ChecksumVerification::Disabled {
+            return Ok(None);
+        }
+
+        let checksum_url = format!("{url}.{}", checksum::CHECKSUM_FILE_EXTENSION);
+        match self.fetch_sidecar(&checksum_url).await? {
+            Some(content) => Ok(Some(checksum::parse_checksum(&content)?)),
+            None if self.checksum_verification == ChecksumVerification::Required => {
+                Err(Error::ChecksumMissing(url.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the minisign signature published alongside the archive at `url`.
+    ///
+    /// Returns `None` if no signature was published and one isn't required for the release type.
+    async fn fetch_published_signature(
+        &self,
+        release_type: &ReleaseType,
+        url: &str,
+    ) -> Result<Option<String>> {
+        if !self.signature_verification.is_enabled_for(release_type) {
+            return Ok(None);
+        }
+
+        let signature_url = format!("{url}.{}", signature::SIGNATURE_FILE_EXTENSION);
+        match self.fetch_sidecar(&signature_url).await? {
+            Some(signature) => Ok(Some(signature)),
+            None if self.signature_verification.is_required_for(release_type) => {
+                Err(Error::SignatureMissing(url.to_string()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Resolves the version of a batch target and downloads its archive from S3, without reporting
+    /// the outcome to `callback`.
+    ///
+    /// The resolved version is returned along with the result, or `None` if resolution failed.
+    async fn download_batch_target(
+        &self,
+        target: &DownloadTarget,
+        dest_dir_path: &Path,
+        callback: &dyn ProgressHandler,
+    ) -> (Option<Version>, Result<PathBuf>) {
+        let version = match &target.version {
+            VersionSelector::Exact(version) => version.clone(),
+            VersionSelector::Requirement(version_req) => {
+                callback.on_progress(ProgressEvent::ResolvingVersion);
+                match self
+                    .resolve_version(&target.release_type, version_req, false)
+                    .await
+                {
+                    Ok(version) => version,
+                    Err(err) => return (None, Err(err)),
+                }
+            }
+        };
+        let result = self
+            .download_archive_from_s3(
+                &target.release_type,
+                &version,
+                &target.platform,
+                &target.archive_type,
+                dest_dir_path,
+                callback,
+            )
+            .await;
+        (Some(version), result)
+    }
+
+    /// Fetches a small text file published alongside an archive, such as its checksum.
+    ///
+    /// Returns `None` if the file doesn't exist.
+    async fn fetch_sidecar(&self, url: &str) -> Result<Option<String>> {
+        let client = &self.http_client;
+        self.retry_policy
+            .run(self.on_retry.as_deref(), move || async move {
+                let response = client.get(url).send().await?;
+                if !response.status().is_success() {
+                    return match get_download_status_error(response.status(), url) {
+                        Error::ReleaseBinaryNotFound(_) => Ok(None),
+                        err => Err(err),
+                    };
+                }
+                Ok(Some(response.text().await?))
+            })
+            .await
+    }
+}
+
+/// Requests the resource at `url`, from `offset` onwards if it's non-zero. The range only applies
+/// if the resource still matches `validator`; otherwise the server sends all of it.
+async fn send_download_request(
+    client: &Client,
+    url: &str,
+    offset: u64,
+    validator: Option<&str>,
+) -> Result<reqwest::Response> {
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+        if let Some(validator) = validator {
+            request = request.header(IF_RANGE, validator);
+        }
+    }
+    Ok(request.send().await?)
+}
+
+/// Returns the value identifying the version of the resource in a response, which can be sent as
+/// `If-Range` to resume it. Weak entity tags can't be used for ranges, so the modification time is
+/// used in their place.
+fn get_validator(response: &reqwest::Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| headers.get(LAST_MODIFIED)?.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Validates that a custom URL points to an archive and returns the path it will be saved to.
+fn get_custom_release_dest_path(url: &str, dest_dir_path: &Path) -> Result<PathBuf> {
+    let file_name = url
+        .split('/')
+        .next_back()
+        .ok_or_else(|| Error::CannotParseFilenameFromUrl)?;
+    if ArchiveType::from_file_name(file_name).is_none() {
+        return Err(Error::UrlIsNotArchive);
+    }
+    Ok(dest_dir_path.join(file_name))
+}
+
+/// Maps an unsuccessful response to an error. Client errors, other than those that indicate the
+/// request may succeed later, mean the binary isn't available at the URL.
+fn get_download_status_error(status: StatusCode, url: &str) -> Error {
+    if status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+    {
+        Error::ReleaseBinaryNotFound(url.to_string())
+    } else {
+        Error::UnexpectedResponseStatus(status.as_u16(), url.to_string())
+    }
+}
+
+/// Returns the path used to hold a download while it is in progress.
+fn get_part_path(dest_path: &Path) -> PathBuf {
+    let mut path = dest_path.as_os_str().to_owned();
+    path.push(".part");
+    PathBuf::from(path)
+}
+
+/// Returns the path the validator of an in-progress download is kept at.
+fn get_validator_path(dest_path: &Path) -> PathBuf {
+    let mut path = get_part_path(dest_path).into_os_string();
+    path.push(".validator");
+    PathBuf::from(path)
+}
+
+async fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Parses a `Content-Range: bytes <start>-<end>/<total>` header into its parts.
+fn parse_content_range(response: &reqwest::Response) -> Option<(u64, Option<u64>)> {
+    let value = response.headers().get("content-range")?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let (start, _) = range.split_once('-')?;
+    Some((start.parse().ok()?, total.parse().ok()))
+}
+
+fn get_content_range_start(response: &reqwest::Response) -> Option<u64> {
+    parse_content_range(response).map(|(start, _)| start)
+}
+
+fn get_content_range_total(response: &reqwest::Response) -> Option<u64> {
+    parse_content_range(response).and_then(|(_, total)| total)
+}
+
+fn get_content_length(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get("content-length")
+        .and_then(|ct_len| ct_len.to_str().ok())
+        .and_then(|ct_len| ct_len.parse::<u64>().ok())
+}
+
+#[async_trait]
+impl AntReleaseRepoActions for AntReleaseRepository {
+    /// Obtains the latest version of a release.
+    ///
+    /// For release types published as crates, the crates.io API is used. For those whose version
+    /// source is an S3 listing, such as the node launchpad, or GitHub releases, the highest stable
+    /// version found there is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing a `String` with the latest version number in the semantic format.
+    /// Otherwise, returns an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The HTTP request to crates.io API, S3 or GitHub fails, after any retries permitted by the
+    ///   retry policy
+    /// - The received JSON data does not have a `crate.newest_version` value
+    /// - There are no stable releases in the S3 bucket or on GitHub
+    async fn get_latest_version(&self, release_type: &ReleaseType) -> Result<Version> {
+        if self.get_version_source(release_type) != VersionSource::CratesIo {
+            return self
+                .list_versions(release_type)
+                .await?
+                .into_iter()
+                .map(|release| release.version)
+                .filter(|version| version.pre.is_empty())
+                .max()
+                .ok_or_else(|| Error::LatestReleaseNotFound(release_type.to_string()));
+        }
+
+        let crate_name = self.get_crate_name(release_type)?;
+        let url = format!("{}/crates/{}", self.crates_io_api_base_url, crate_name);
+        let json = self.get_crates_io_json(&url).await?;
+
+        if let Some(version) = json["crate"]["newest_version"].as_str() {
+            return Ok(Version::parse(version)?);
+        }
+
+        Err(Error::LatestReleaseNotFound(release_type.to_string()))
+    }
+
+    /// Obtains the highest version of a release that is published on a particular channel.
+    ///
+    /// Unlike `get_latest_version`, which returns whatever crates.io reports as the newest
+    /// version, this considers every published version and picks the highest one the channel
+    /// accepts. Yanked versions are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    /// * `channel` - The channel to track.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The versions could not be listed
+    /// - There are no versions on the channel
+    async fn get_latest_version_for_channel(
+        &self,
+        release_type: &ReleaseType,
+        channel: &Channel,
+    ) -> Result<Version> {
+        self.list_versions(release_type)
+            .await?
+            .into_iter()
+            .filter(|release| !release.yanked && channel.accepts(&release.version))
+            .map(|release| release.version)
+            .max()
+            .ok_or_else(|| {
+                Error::LatestReleaseNotFound(format!("{release_type} on the {channel} channel"))
+            })
+    }
+
+    /// Obtains every published version of a release.
+    ///
+    /// The versions come from the crates.io API, or for release types whose version source is an
+    /// S3 listing or GitHub releases, from the names of the archives in the bucket or the release
+    /// tags. Versions discovered from S3 or GitHub are never reported as yanked.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the versions, ordered from highest to lowest, each with its
+    /// publication date and whether it has been yanked. Otherwise, returns an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The release type is not published to crates.io and its version source is crates.io
+    /// - The HTTP request to crates.io API, S3 or GitHub fails, after any retries permitted by the
+    ///   retry policy
+    /// - The received JSON data does not have a `versions` array, or an entry has an invalid
+    ///   version number or publication date
+    /// - The S3 bucket listing or GitHub releases could not be parsed
+    async fn list_versions(&self, release_type: &ReleaseType) -> Result<Vec<ReleaseInfo>> {
+        let mut releases = match self.get_version_source(release_type) {
+            VersionSource::CratesIo => self.list_versions_from_crates_io(release_type).await?,
+            VersionSource::S3Listing => self.list_versions_from_s3(release_type).await?,
+            VersionSource::GitHubReleases => self.list_versions_from_github(release_type).await?,
+        };
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(releases)
+    }
+
+    /// Builds an index of the release archives in the S3 bucket of a release type.
+    ///
+    /// The bucket is listed with `ListObjectsV2` requests, following continuation tokens until the
+    /// whole listing has been retrieved, and each key is parsed as an archive name of the form
+    /// `<release type>-<version>-<target triple>.<extension>`.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the index, with the version, platform, archive type, size and
+    /// modification time of each archive. Keys that are not archives of the release type are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The HTTP request to S3 fails or is unsuccessful, after any retries permitted by the retry
+    ///   policy
+    /// - The bucket listing could not be parsed
+    async fn get_s3_release_index(&self, release_type: &ReleaseType) -> Result<S3ReleaseIndex> {
+        let prefix = format!("{release_type}-");
+        let objects = self
+            .with_mirrors(release_type, |base_url| {
+                let prefix = &prefix;
+                async move { self.list_s3_objects(&base_url, prefix).await }
+            })
+            .await?;
+        Ok(S3ReleaseIndex::from_objects(release_type, objects))
+    }
+
+    /// Lists the GitHub releases of a release type.
+    ///
+    /// Every page of the repository's releases is retrieved, following the `Link` header, and the
+    /// releases whose tags name the release type, e.g., `antnode-v0.112.7`, are kept. Drafts are
+    /// left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the releases, ordered from highest version to lowest, each
+    /// with the assets attached to it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The HTTP request to the GitHub API fails, after any retries permitted by the retry policy
+    /// - The rate limit of the GitHub API has been exhausted
+    /// - The `Link` header or the received JSON data could not be parsed
+    async fn list_github_releases(&self, release_type: &ReleaseType) -> Result<Vec<GitHubRelease>> {
+        let mut releases = Vec::new();
+        let mut url = Some(format!(
+            "{}/repos/{}/releases?per_page=100",
+            self.github_api_base_url, self.github_repository
+        ));
+        while let Some(page_url) = url {
+            let (json, next_page_url) = self.get_github_json(&page_url).await?;
+            releases.extend(
+                github::parse_releases(&json)?
+                    .into_iter()
+                    .filter(|release| release.release_type == *release_type),
+            );
+            url = next_page_url;
+        }
+        releases.sort_by(|a, b| b.version.cmp(&a.version));
+        Ok(releases)
+    }
+
+    /// Finds the highest published version of a release that satisfies a version requirement.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to look for.
+    /// * `version_req` - The requirement, e.g., `^0.112` or `~0.11.4`.
+    /// * `include_prerelease` - Whether pre-release versions may be selected. By the usual semver
+    ///   rules, a requirement only matches a pre-release if it names one with the same major,
+    ///   minor and patch version. When this is set, a pre-release is considered to match if the
+    ///   version it leads up to does.
+    ///
+    /// # Returns
+    ///
+    /// Returns a `Result` containing the selected version. Yanked versions are never selected.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - The versions could not be listed
+    /// - No version matches, in which case the error lists the versions that were available
+    async fn resolve_version(
+        &self,
+        release_type: &ReleaseType,
+        version_req: &VersionReq,
+        include_prerelease: bool,
+    ) -> Result<Version> {
+        let candidates: Vec<Version> = self
+            .list_versions(release_type)
+            .await?
+            .into_iter()
+            .filter(|release| !release.yanked)
+            .map(|release| release.version)
+            .collect();
+
+        candidates
+            .iter()
+            .filter(|version| version_matches(version_req, version, include_prerelease))
+            .max()
+            .cloned()
+            .ok_or_else(|| Error::NoMatchingVersion {
+                release_type: release_type.to_string(),
+                version_req: version_req.to_string(),
+                candidates,
+            })
+    }
+
+    /// Downloads a release binary archive from S3.
+    ///
+    /// # Arguments
+    ///
+    /// - `release_type`: The type of release.
+    /// - `version`: The version of the release.
+    /// - `platform`: The target platform.
+    /// - `archive_type`: The type of archive (e.g., tar.gz, zip).
+    /// - `dest_path`: The directory where the downloaded archive will be stored.
+    /// - `callback`: A callback function that can be used for download progress.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `PathBuf` indicating the full path of the downloaded archive, or an error if
+    /// the download or file write operation fails.
+    ///
+    /// If a SHA-256 checksum has been published alongside the archive, the archive is verified
+    /// against it, subject to the repository's `checksum_verification` setting. Likewise, the
+    /// archive's minisign signature is checked against the trusted keys in
+    /// `signature_verification`, and the archive is deleted if the signature doesn't validate.
+    ///
+    /// If the repository has a cache, a release that was downloaded previously is served from the
+    /// cache without any network access, and newly downloaded archives are added to it, after
+    /// which the cache is pruned according to its policy.
+    async fn download_release_from_s3(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf> {
+        let result = self
+            .download_archive_from_s3(
+                release_type,
+                version,
+                platform,
+                archive_type,
+                dest_path,
+                callback,
+            )
+            .await;
+        progress::report_outcome(callback, result)
+    }
+
+    /// Downloads a release archive attached to a GitHub release.
+    ///
+    /// The release is looked up by its tag, e.g., `antnode-v0.112.7`, and the asset is selected by
+    /// its name, e.g., `antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz`. The archive is verified
+    /// as for `download_release_from_s3`, using the SHA-256 digest GitHub reports for the asset, or
+    /// else a `.sha256` asset, and a `.sig` asset for its signature.
+    ///
+    /// # Arguments
+    ///
+    /// * `release_type` - A reference to a `ReleaseType` enum specifying the type of release to download.
+    /// * `version` - The version of the release to download.
+    /// * `platform` - A reference to a `Platform` enum specifying the platform of the release to download.
+    /// * `archive_type` - A reference to an `ArchiveType` enum specifying the type of archive to download.
+    /// * `dest_path` - A reference to a `Path` representing the directory to save the archive to.
+    /// * `callback` - A callback function that can be used for download progress.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the path of the downloaded archive.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - There is no GitHub release for the version, or it has no archive for the platform
+    /// - The rate limit of the GitHub API has been exhausted
+    /// - A checksum or signature is required by the configured verification, but isn't published
+    /// - The download fails, or its checksum or signature doesn't match
+    async fn download_release_from_github(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf> {
+        let result: Result<PathBuf> = async {
+            let tag_name = format!("{release_type}-v{version}");
+            let url = format!(
+                "{}/repos/{}/releases/tags/{}",
+                self.github_api_base_url, self.github_repository, tag_name
+            );
+            let (json, _) = self.get_github_json(&url).await.map_err(|err| match err {
+                Error::UnexpectedResponseStatus(404, _) => {
+                    Error::ReleaseBinaryNotFound(tag_name.clone())
+                }
+                err => err,
+            })?;
+            let release =
+                github::parse_release(&json)?.ok_or(Error::TagNameVersionParsingFailed)?;
+
+            let asset = release.find_asset(platform, archive_type).ok_or_else(|| {
+                Error::ReleaseBinaryNotFound(format!(
+                    "{release_type}-{version}-{platform}.{archive_type}"
+                ))
+            })?;
+            // The digest GitHub reports for the asset is used as its checksum, falling back to a
+            // checksum attached to the release, so the same verification applies as for S3.
+            let expected_sha256 = match &asset.sha256 {
+                Some(sha256) if self.checksum_verification != ChecksumVerification::Disabled => {
+                    Some(sha256.clone())
+                }
+                _ => self.fetch_published_checksum(&asset.download_url).await?,
+            };
+            let signature = self
+                .fetch_published_signature(release_type, &asset.download_url)
+                .await?;
+
+            let archive_path = dest_path.join(&asset.name);
+            self.download_url(
+                &asset.download_url,
+                &archive_path,
+                expected_sha256.as_deref(),
+                callback,
+            )
+            .await?;
+            self.verify_downloaded_signature(&archive_path, signature.as_deref(), callback)
+                .await?;
+
+            Ok(archive_path)
+        }
+        .await;
+        progress::report_outcome(callback, result)
+    }
+
+    async fn download_release(
+        &self,
+        url: &str,
+        dest_dir_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf> {
+        let result = match get_custom_release_dest_path(url, dest_dir_path) {
+            Ok(dest_path) => self
+                .download_url(url, &dest_path, None, callback)
+                .await
+                .map(|_| dest_path),
+            Err(err) => Err(err),
+        };
+        progress::report_outcome(callback, result)
+    }
+
+    /// Downloads a release archive from a custom URL and verifies it against a SHA-256 digest
+    /// supplied by the caller.
+    ///
+    /// # Arguments
+    ///
+    /// - `url`: The URL of the archive.
+    /// - `expected_sha256`: The hex-encoded SHA-256 digest the archive is expected to have.
+    /// - `dest_dir_path`: The directory where the downloaded archive will be stored.
+    /// - `callback`: A callback function that can be used for download progress.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `PathBuf` indicating the full path of the downloaded archive. If the digest
+    /// of the downloaded data doesn't match, the file is deleted and `Error::ChecksumMismatch` is
+    /// returned.
+    async fn download_release_with_checksum(
+        &self,
+        url: &str,
+        expected_sha256: &str,
+        dest_dir_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf> {
+        let result = async {
+            let expected_sha256 = checksum::parse_checksum(expected_sha256)?;
+            let dest_path = get_custom_release_dest_path(url, dest_dir_path)?;
+            self.download_url(url, &dest_path, Some(&expected_sha256), callback)
+                .await?;
+            Ok(dest_path)
+        }
+        .await;
+        progress::report_outcome(callback, result)
+    }
+
+    async fn download_winsw(&self, dest_path: &Path, callback: &ProgressCallback) -> Result<()> {
+        let result = self
+            .download_url(&self.winsw_url, dest_path, None, callback)
+            .await;
+        progress::report_outcome_at(callback, dest_path, result)
+    }
+
+    /// Downloads a release and extracts its binary, without keeping the archive.
+    ///
+    /// Tarballs are decompressed as they are downloaded, so only the binary is written to disk.
+    /// The published checksum and signature are verified over the compressed data, as they are
+    /// for `download_release_from_s3`, and the binary is only moved into `dest_dir_path` once the
+    /// verification has passed. Because decompression can't pick up part way through an archive,
+    /// a failed download is retried from the beginning rather than resumed.
+    ///
+    /// Zip archives can only be read with random access, so they are downloaded to a temporary
+    /// file first. The same applies to any archive when the repository has a cache, so that the
+    /// archive can be added to it.
+    ///
+    /// # Arguments
+    ///
+    /// - `release_type`: The type of release.
+    /// - `version`: The version of the release.
+    /// - `platform`: The target platform.
+    /// - `archive_type`: The type of archive the release is published in.
+    /// - `dest_dir_path`: The directory the binary will be extracted to.
+    /// - `callback`: A callback function that can be used for download progress.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `PathBuf` indicating the full path of the extracted binary, which is named
+    /// after the release type, e.g., `antnode`, or `antnode.exe` on Windows.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if:
+    /// - Extraction of the archive type isn't enabled by the crate's features
+    /// - The download fails, or the archive's checksum or signature doesn't verify
+    /// - The archive doesn't contain the binary, or has an entry that would be extracted outside
+    ///   the destination
+    async fn download_and_extract(
+        &self,
+        release_type: &ReleaseType,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+        dest_dir_path: &Path,
+        callback: &ProgressCallback,
+    ) -> Result<PathBuf> {
+        let result = self
+            .download_and_extract_binary(
+                release_type,
+                version,
+                platform,
+                archive_type,
+                dest_dir_path,
+                callback,
+            )
+            .await;
+        progress::report_outcome(callback, result)
+    }
+
+    /// Downloads several release archives from S3 concurrently.
+    ///
+    /// At most `max_concurrent_downloads` downloads run at once, all sharing the repository's HTTP
+    /// client. Each one behaves like `download_release_from_s3`, after a target with a version
+    /// requirement has been resolved to the highest matching version that hasn't been yanked.
+    ///
+    /// # Arguments
+    ///
+    /// - `targets`: The releases to download.
+    /// - `dest_dir_path`: The directory where the downloaded archives will be stored.
+    /// - `callback`: Receives the progress of each download, along with the index of its target.
+    ///
+    /// # Returns
+    ///
+    /// A `BatchDownloadResult` for each target, in the same order as `targets`. Failing to resolve
+    /// or download one target doesn't affect the others.
+    async fn download_releases(
+        &self,
+        targets: &[DownloadTarget],
+        dest_dir_path: &Path,
+        callback: &BatchProgressCallback,
+    ) -> Vec<BatchDownloadResult> {
+        let downloads: Vec<_> = targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| async move {
+                let target_callback = move |event: ProgressEvent| callback(index, event);
+                let (version, result) = self
+                    .download_batch_target(target, dest_dir_path, &target_callback)
+                    .await;
+                let result = progress::report_outcome(&target_callback, result);
+                let download_result = BatchDownloadResult {
+                    target: target.clone(),
+                    version,
+                    result,
+                };
+                (index, download_result)
+            })
+            .collect();
+
+        let mut results: Vec<(usize, BatchDownloadResult)> = stream::iter(downloads)
+            .buffer_unordered(self.max_concurrent_downloads.max(1))
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
 
     /// Extracts a release binary archive.
     ///
-    /// The archive will include a single binary file.
+    /// The archive will include a single binary file. Only the first entry is extracted, so for
+    /// archives with more than one file, use `extract_binary_from_archive` or
+    /// `extract_all_from_archive` instead.
+    ///
+    /// The Unix mode and modification time recorded in the archive are applied to the binary,
+    /// unless `binary_permissions` is set to make it executable regardless.
     ///
     /// # Arguments
     ///
@@ -350,49 +2027,72 @@ impl AntReleaseRepoActions for AntReleaseRepository {
         archive_path: &Path,
         dest_dir_path: &Path,
     ) -> Result<PathBuf> {
-        if !archive_path.exists() {
-            return Err(Error::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                format!("Archive not found at: {:?}", archive_path),
-            )));
-        }
-
-        if archive_path.extension() == Some(std::ffi::OsStr::new("gz")) {
-            let archive_file = std::fs::File::open(archive_path)?;
-            let tarball = flate2::read::GzDecoder::new(archive_file);
-            let mut archive = Archive::new(tarball);
-            if let Some(file) = (archive.entries()?).next() {
-                let mut file = file?;
-                let out_path = dest_dir_path.join(file.path()?);
-                file.unpack(&out_path)?;
-                return Ok(out_path);
-            }
-        } else if archive_path.extension() == Some(std::ffi::OsStr::new("zip")) {
-            let archive_file = std::fs::File::open(archive_path)?;
-            let mut archive = ZipArchive::new(archive_file)?;
-            if let Some(i) = (0..archive.len()).next() {
-                let mut file = archive.by_index(i)?;
-                let out_path = dest_dir_path.join(file.name());
-                if file.name().ends_with('/') {
-                    std::fs::create_dir_all(&out_path)?;
-                } else {
-                    let mut outfile = std::fs::File::create(&out_path)?;
-                    std::io::copy(&mut file, &mut outfile)?;
-                }
-                return Ok(out_path);
-            }
-        } else {
-            return Err(Error::Io(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Unsupported archive format",
-            )));
-        }
+        extract::extract_first(archive_path, dest_dir_path, self.binary_permissions)
+    }
+
+    /// Extracts every file and directory in a release archive.
+    ///
+    /// The Unix modes and modification times recorded in the archive are applied to the files.
+    ///
+    /// # Arguments
+    ///
+    /// - `archive_path`: The path of the archive file to extract.
+    /// - `dest_dir_path`: The directory where the archive should be extracted. The directory
+    ///   structure of the archive is kept beneath it.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with an `ExtractionManifest` listing the archive entries, in order, and the
+    /// paths they were extracted to.
+    fn extract_all_from_archive(
+        &self,
+        archive_path: &Path,
+        dest_dir_path: &Path,
+    ) -> Result<ExtractionManifest> {
+        extract::extract_all(archive_path, dest_dir_path)
+    }
+
+    /// Extracts a single named binary from a release archive that may contain other files.
+    ///
+    /// The binary is matched on its file name, wherever it is within the archive, and a name
+    /// without an extension also matches a Windows binary, e.g., `antctl` matches `antctl.exe`.
+    /// Its permissions are set according to `binary_permissions`.
+    ///
+    /// # Arguments
+    ///
+    /// - `archive_path`: The path of the archive file to extract.
+    /// - `binary_name`: The file name of the binary, e.g., `antctld`.
+    /// - `dest_dir_path`: The directory the binary should be extracted to.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` with `PathBuf` indicating the full path of the extracted binary. If the archive
+    /// doesn't contain the binary, `Error::BinaryNotFoundInArchive` is returned with the list of
+    /// files it does contain.
+    fn extract_binary_from_archive(
+        &self,
+        archive_path: &Path,
+        binary_name: &str,
+        dest_dir_path: &Path,
+    ) -> Result<PathBuf> {
+        extract::extract_binary(
+            archive_path,
+            binary_name,
+            dest_dir_path,
+            self.binary_permissions,
+        )
+    }
+}
 
-        Err(Error::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Failed to extract archive",
-        )))
+fn version_matches(version_req: &VersionReq, version: &Version, include_prerelease: bool) -> bool {
+    if version_req.matches(version) {
+        return true;
+    }
+    if include_prerelease && !version.pre.is_empty() {
+        let release = Version::new(version.major, version.minor, version.patch);
+        return version_req.matches(&release);
     }
+    false
 }
 
 pub fn get_running_platform() -> Result<Platform> {
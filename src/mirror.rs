@@ -0,0 +1,111 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Error;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a mirror that failed is avoided for, unless configured otherwise.
+pub const DEFAULT_MIRROR_COOL_DOWN: Duration = Duration::from_secs(5 * 60);
+
+/// Kinds of IO error that mean the connection to a mirror failed while data was being streamed
+/// from it, as opposed to, e.g., the destination being unwritable.
+const CONNECTION_IO_ERROR_KINDS: &[ErrorKind] = &[
+    ErrorKind::BrokenPipe,
+    ErrorKind::ConnectionAborted,
+    ErrorKind::ConnectionReset,
+    ErrorKind::TimedOut,
+    ErrorKind::UnexpectedEof,
+];
+
+/// Remembers when each mirror last failed, so that later downloads avoid it for a while.
+///
+/// Clones share the same record, so repositories that are cloned from one another, or that are
+/// given the same `MirrorHealth`, learn from each other's failures.
+#[derive(Clone, Debug, Default)]
+pub struct MirrorHealth {
+    failures: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl MirrorHealth {
+    /// Returns whether the mirror failed less than `cool_down` ago.
+    pub fn is_cooling_down(&self, base_url: &str, cool_down: Duration) -> bool {
+        self.failures
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(base_url)
+            .is_some_and(|failed_at| failed_at.elapsed() < cool_down)
+    }
+
+    pub(crate) fn record_failure(&self, base_url: &str) {
+        self.failures
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(base_url.to_string(), Instant::now());
+    }
+
+    pub(crate) fn record_success(&self, base_url: &str) {
+        self.failures
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .remove(base_url);
+    }
+
+    /// Puts the mirrors in the order they should be tried: those that haven't failed recently
+    /// keep their configured order, followed by those that are cooling down. The latter are still
+    /// tried as a last resort, because a download that might succeed is better than none.
+    pub(crate) fn order(&self, base_urls: Vec<String>, cool_down: Duration) -> Vec<String> {
+        let (cooling_down, available): (Vec<String>, Vec<String>) = base_urls
+            .into_iter()
+            .partition(|base_url| self.is_cooling_down(base_url, cool_down));
+        available.into_iter().chain(cooling_down).collect()
+    }
+}
+
+/// Returns whether an error means the mirror is unavailable, so a different mirror may succeed.
+///
+/// Connection failures, including those partway through a response, timeouts and server errors
+/// count. Errors such as a missing release or a checksum mismatch are returned without trying
+/// other mirrors.
+pub(crate) fn is_mirror_failure(error: &Error) -> bool {
+    match error {
+        Error::ReqwestError(err) => {
+            err.is_connect()
+                || err.is_timeout()
+                || err.is_body()
+                || err.is_decode()
+                || err.status().is_some_and(|status| status.is_server_error())
+                || has_connection_io_source(err)
+        }
+        Error::UnexpectedResponseStatus(status, _) => (500..600).contains(status),
+        Error::Io(err) => is_connection_io_error(err),
+        _ => false,
+    }
+}
+
+fn is_connection_io_error(err: &std::io::Error) -> bool {
+    CONNECTION_IO_ERROR_KINDS.contains(&err.kind())
+}
+
+/// Checks the chain of underlying errors for a connection IO error, which is how a response body
+/// that was cut off is reported.
+fn has_connection_io_source(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = err.source();
+    while let Some(inner) = source {
+        if inner
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(is_connection_io_error)
+        {
+            return true;
+        }
+        source = inner.source();
+    }
+    false
+}
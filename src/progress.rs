@@ -0,0 +1,107 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+use tokio::sync::{mpsc, watch};
+
+/// The stages an operation on a release goes through, reported as they happen.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProgressEvent {
+    /// The version to download is being determined, e.g., from a version requirement.
+    ResolvingVersion,
+    /// A request for the archive is about to be sent.
+    Connecting,
+    /// Some of the archive has been received. The total is `None` when the server didn't say how
+    /// large the archive is.
+    Downloading { downloaded: u64, total: Option<u64> },
+    /// The previous attempt failed with a transient error and the given attempt is about to be
+    /// made.
+    Retrying { attempt: u32, reason: String },
+    /// The archive is being checked against its published checksum or signature.
+    Verifying,
+    /// The binary is being unpacked from the archive.
+    Extracting,
+    /// The operation succeeded, producing the file at `path`.
+    Completed { path: PathBuf },
+    /// The operation failed. No further events are reported for it.
+    Failed { reason: String },
+}
+
+/// Receives the progress of an operation on a release.
+///
+/// This is implemented for closures taking a `ProgressEvent`, and for the sending half of a
+/// `tokio::sync::mpsc` unbounded channel or a `tokio::sync::watch` channel. A callback with the
+/// original `Fn(downloaded, total)` signature can be wrapped in `ByteProgress`.
+pub trait ProgressHandler: Send + Sync {
+    fn on_progress(&self, event: ProgressEvent);
+}
+
+impl<F> ProgressHandler for F
+where
+    F: Fn(ProgressEvent) + Send + Sync,
+{
+    fn on_progress(&self, event: ProgressEvent) {
+        self(event)
+    }
+}
+
+/// Events are sent on the channel. They are discarded once the receiver has been dropped.
+impl ProgressHandler for mpsc::UnboundedSender<ProgressEvent> {
+    fn on_progress(&self, event: ProgressEvent) {
+        let _ = self.send(event);
+    }
+}
+
+/// The channel holds the most recent event, which suits a caller that polls for the current state
+/// rather than wanting every event.
+impl ProgressHandler for watch::Sender<ProgressEvent> {
+    fn on_progress(&self, event: ProgressEvent) {
+        self.send_replace(event);
+    }
+}
+
+/// Adapts a callback taking the number of bytes downloaded and the total size, which is how
+/// progress was reported before `ProgressEvent` was introduced.
+///
+/// Only `Downloading` events are passed on, with a total of 0 when the size isn't known.
+pub struct ByteProgress<F>(pub F);
+
+impl<F> ProgressHandler for ByteProgress<F>
+where
+    F: Fn(u64, u64) + Send + Sync,
+{
+    fn on_progress(&self, event: ProgressEvent) {
+        if let ProgressEvent::Downloading { downloaded, total } = event {
+            (self.0)(downloaded, total.unwrap_or(0))
+        }
+    }
+}
+
+/// Reports the outcome of an operation as a `Completed` or `Failed` event, passing the result on.
+pub(crate) fn report_outcome(
+    handler: &dyn ProgressHandler,
+    result: Result<PathBuf>,
+) -> Result<PathBuf> {
+    match &result {
+        Ok(path) => handler.on_progress(ProgressEvent::Completed { path: path.clone() }),
+        Err(err) => handler.on_progress(ProgressEvent::Failed {
+            reason: err.to_string(),
+        }),
+    }
+    result
+}
+
+/// Like `report_outcome`, for an operation that writes to a path given by the caller.
+pub(crate) fn report_outcome_at(
+    handler: &dyn ProgressHandler,
+    path: &Path,
+    result: Result<()>,
+) -> Result<()> {
+    report_outcome(handler, result.map(|_| path.to_path_buf())).map(|_| ())
+}
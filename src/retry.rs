@@ -0,0 +1,147 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use std::future::Future;
+use std::io::ErrorKind;
+use std::time::Duration;
+
+/// Called before a failed operation is retried, with the number of the attempt about to be made
+/// and the error that caused the previous attempt to fail.
+pub type RetryCallback = dyn Fn(u32, &Error) + Send + Sync;
+
+/// A `RetryCallback` that may borrow from the operation being retried.
+pub(crate) type RetryNotifier<'a> = dyn Fn(u32, &Error) + Send + Sync + 'a;
+
+/// Controls how network operations are retried when they fail with a transient error.
+///
+/// The delay before each retry grows exponentially from `base_delay`, doubling with every attempt,
+/// and is capped at `max_delay`. With `jitter` enabled, a random amount of up to half the delay is
+/// subtracted, to avoid many clients retrying in lockstep.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The total number of attempts, including the first. A value of 1 disables retries.
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub jitter: bool,
+    /// HTTP status codes that indicate a transient failure.
+    pub retryable_status_codes: Vec<u16>,
+    /// Kinds of IO error that indicate a transient failure.
+    pub retryable_io_error_kinds: Vec<ErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            retryable_status_codes: vec![408, 429, 500, 502, 503, 504],
+            retryable_io_error_kinds: vec![
+                ErrorKind::BrokenPipe,
+                ErrorKind::ConnectionAborted,
+                ErrorKind::ConnectionRefused,
+                ErrorKind::ConnectionReset,
+                ErrorKind::Interrupted,
+                ErrorKind::TimedOut,
+                ErrorKind::UnexpectedEof,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt and never retries.
+    pub fn no_retries() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the delay to wait after the given (1-based) attempt has failed.
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+        if self.jitter {
+            let max_jitter = delay.as_millis() as u64 / 2;
+            delay.saturating_sub(Duration::from_millis(fastrand::u64(0..=max_jitter)))
+        } else {
+            delay
+        }
+    }
+
+    /// Determines whether an operation that failed with `error` is worth attempting again.
+    pub fn is_retryable(&self, error: &Error) -> bool {
+        match error {
+            Error::CratesIoResponseError(status) | Error::UnexpectedResponseStatus(status, _) => {
+                self.retryable_status_codes.contains(status)
+            }
+            Error::Io(err) => self.retryable_io_error_kinds.contains(&err.kind()),
+            Error::ReqwestError(err) => match err.status() {
+                Some(status) => self.retryable_status_codes.contains(&status.as_u16()),
+                None => {
+                    err.is_connect()
+                        || err.is_timeout()
+                        || err.is_request()
+                        || err.is_body()
+                        || self.has_retryable_io_source(err)
+                }
+            },
+            _ => false,
+        }
+    }
+
+    /// Checks the chain of underlying errors for an IO error of a retryable kind, which is how an
+    /// interrupted response body is reported.
+    fn has_retryable_io_source(&self, err: &(dyn std::error::Error + 'static)) -> bool {
+        let mut source = err.source();
+        while let Some(inner) = source {
+            if let Some(io_err) = inner.downcast_ref::<std::io::Error>() {
+                if self.retryable_io_error_kinds.contains(&io_err.kind()) {
+                    return true;
+                }
+            }
+            source = inner.source();
+        }
+        false
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error that isn't retryable, or the
+    /// maximum number of attempts has been made.
+    pub(crate) async fn run<T, F, Fut>(
+        &self,
+        on_retry: Option<&RetryNotifier<'_>>,
+        mut operation: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.max_attempts && self.is_retryable(&err) => {
+                    let delay = self.delay_for_attempt(attempt);
+                    attempt += 1;
+                    if let Some(on_retry) = on_retry {
+                        on_retry(attempt, &err);
+                    }
+                    tokio::time::sleep(delay).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::{ArchiveType, Platform, ReleaseArtifact, ReleaseType};
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use semver::Version;
+
+lazy_static! {
+    static ref CONTENTS_REGEX: Regex = element_regex("Contents");
+    static ref KEY_REGEX: Regex = element_regex("Key");
+    static ref SIZE_REGEX: Regex = element_regex("Size");
+    static ref LAST_MODIFIED_REGEX: Regex = element_regex("LastModified");
+    static ref IS_TRUNCATED_REGEX: Regex = element_regex("IsTruncated");
+    static ref NEXT_CONTINUATION_TOKEN_REGEX: Regex = element_regex("NextContinuationToken");
+}
+
+/// An object in an S3 bucket, as described by a `ListObjectsV2` response.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct S3Object {
+    pub key: String,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// A single page of a `ListObjectsV2` response.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub(crate) struct ListObjectsPage {
+    pub objects: Vec<S3Object>,
+    /// The token to request the next page with, if the listing was truncated.
+    pub next_continuation_token: Option<String>,
+}
+
+/// Parses the XML body of a `ListObjectsV2` (`?list-type=2`) response.
+pub(crate) fn parse_list_objects_response(xml: &str) -> Result<ListObjectsPage> {
+    let mut objects = Vec::new();
+    for contents in CONTENTS_REGEX.captures_iter(xml) {
+        let contents = &contents[1];
+        let key = get_element(contents, &KEY_REGEX)
+            .ok_or_else(|| Error::S3ListingParseError("object without a key".to_string()))?;
+        let size = get_element(contents, &SIZE_REGEX)
+            .and_then(|size| size.parse::<u64>().ok())
+            .ok_or_else(|| Error::S3ListingParseError(format!("no valid size for {key}")))?;
+        let last_modified = get_element(contents, &LAST_MODIFIED_REGEX)
+            .ok_or_else(|| Error::S3ListingParseError(format!("no modified date for {key}")))?;
+        objects.push(S3Object {
+            last_modified: DateTime::parse_from_rfc3339(&last_modified)?.with_timezone(&Utc),
+            key,
+            size,
+        });
+    }
+
+    let is_truncated = get_element(xml, &IS_TRUNCATED_REGEX).as_deref() == Some("true");
+    let next_continuation_token = if is_truncated {
+        Some(
+            get_element(xml, &NEXT_CONTINUATION_TOKEN_REGEX).ok_or_else(|| {
+                Error::S3ListingParseError("truncated listing without a continuation token".into())
+            })?,
+        )
+    } else {
+        None
+    };
+
+    Ok(ListObjectsPage {
+        objects,
+        next_continuation_token,
+    })
+}
+
+/// Returns a regex capturing the content of a `<name>` element.
+fn element_regex(name: &str) -> Regex {
+    Regex::new(&format!(r"(?s)<{name}>(.*?)</{name}>")).expect("element names form valid regexes")
+}
+
+/// Returns the unescaped text of the first element in `xml` matched by an `element_regex`.
+fn get_element(xml: &str, element_regex: &Regex) -> Option<String> {
+    element_regex
+        .captures(xml)
+        .map(|c| unescape_xml(c[1].trim()))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// A release archive found in an S3 bucket.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct S3ReleaseEntry {
+    pub key: String,
+    pub release_type: ReleaseType,
+    pub version: Version,
+    pub platform: Platform,
+    pub archive_type: ArchiveType,
+    pub size: u64,
+    pub last_modified: DateTime<Utc>,
+}
+
+/// An index of the release archives in an S3 bucket, built from a listing of its objects.
+///
+/// Objects whose keys are not release archive names, such as `antnode-latest.txt`, are left out of
+/// the index.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct S3ReleaseIndex {
+    entries: Vec<S3ReleaseEntry>,
+}
+
+impl S3ReleaseIndex {
+    /// Builds an index from the objects in a bucket, keeping only the archives of `release_type`.
+    pub fn from_objects(release_type: &ReleaseType, objects: Vec<S3Object>) -> Self {
+        let mut entries: Vec<S3ReleaseEntry> = objects
+            .into_iter()
+            .filter_map(|object| {
+                let artifact = ReleaseArtifact::parse(&object.key).ok()?;
+                if artifact.release_type != *release_type {
+                    return None;
+                }
+                Some(S3ReleaseEntry {
+                    key: object.key,
+                    release_type: artifact.release_type,
+                    version: artifact.version,
+                    platform: artifact.platform,
+                    archive_type: artifact.archive_type,
+                    size: object.size,
+                    last_modified: object.last_modified,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.version.cmp(&a.version).then_with(|| a.key.cmp(&b.key)));
+        Self { entries }
+    }
+
+    /// Returns every archive in the index, ordered from the highest version to the lowest.
+    pub fn entries(&self) -> &[S3ReleaseEntry] {
+        &self.entries
+    }
+
+    /// Returns the distinct versions in the index, from the highest to the lowest.
+    pub fn versions(&self) -> Vec<Version> {
+        let mut versions: Vec<Version> = self.entries.iter().map(|e| e.version.clone()).collect();
+        versions.dedup();
+        versions
+    }
+
+    /// Returns the platforms that `version` has an archive for.
+    pub fn platforms(&self, version: &Version) -> Vec<Platform> {
+        let mut platforms = Vec::new();
+        for entry in self.entries.iter().filter(|e| e.version == *version) {
+            if !platforms.contains(&entry.platform) {
+                platforms.push(entry.platform);
+            }
+        }
+        platforms
+    }
+
+    /// Returns the archive of `version` for the given platform and archive type, if there is one.
+    pub fn find(
+        &self,
+        version: &Version,
+        platform: &Platform,
+        archive_type: &ArchiveType,
+    ) -> Option<&S3ReleaseEntry> {
+        self.entries.iter().find(|e| {
+            e.version == *version && e.platform == *platform && e.archive_type == *archive_type
+        })
+    }
+
+    /// Returns the time each version was published, taken as the upload time of its first
+    /// archive, from the highest version to the lowest.
+    pub fn published_dates(&self) -> Vec<(Version, DateTime<Utc>)> {
+        let mut dates: Vec<(Version, DateTime<Utc>)> = Vec::new();
+        for entry in &self.entries {
+            match dates.last_mut() {
+                Some((version, at)) if *version == entry.version => {
+                    *at = (*at).min(entry.last_modified);
+                }
+                _ => dates.push((entry.version.clone(), entry.last_modified)),
+            }
+        }
+        dates
+    }
+}
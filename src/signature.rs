@@ -0,0 +1,129 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use crate::error::{Error, Result};
+use crate::ReleaseType;
+use minisign_verify::{PublicKey, Signature, StreamVerifier};
+use std::collections::HashSet;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// The extension of the detached minisign signature published next to each archive.
+pub const SIGNATURE_FILE_EXTENSION: &str = "sig";
+
+/// Configures verification of the detached minisign signatures published alongside archives.
+///
+/// When any trusted keys are configured, an archive is only accepted if its published signature
+/// was made by one of those keys. An archive without a signature is rejected too, since otherwise
+/// whoever serves the archives could skip verification by not serving the signature, unless its
+/// release type has been opted out in `optional_for`.
+///
+/// The signature must also name the archive it was made for in its trusted comment, as minisign's
+/// default `file:<name>` comment does. Otherwise any other archive signed by a trusted key, such
+/// as an older release, could be served in place of the one that was asked for.
+#[derive(Clone, Debug, Default)]
+pub struct SignatureVerification {
+    /// Minisign public keys, either as the base64 key alone or the full content of a
+    /// `minisign.pub` file.
+    pub trusted_public_keys: Vec<String>,
+    /// Release types that may be accepted without a signature, e.g., because they aren't signed
+    /// yet. A signature that is published for them is still verified.
+    pub optional_for: HashSet<ReleaseType>,
+}
+
+impl SignatureVerification {
+    /// Returns whether a signature should be looked for when downloading `release_type`.
+    pub fn is_enabled_for(&self, _release_type: &ReleaseType) -> bool {
+        !self.trusted_public_keys.is_empty()
+    }
+
+    /// Returns whether an archive of `release_type` must be rejected if it has no signature.
+    pub fn is_required_for(&self, release_type: &ReleaseType) -> bool {
+        self.is_enabled_for(release_type) && !self.optional_for.contains(release_type)
+    }
+
+    pub(crate) fn parse_public_keys(&self) -> Result<Vec<PublicKey>> {
+        self.trusted_public_keys
+            .iter()
+            .map(|key| {
+                let key = key.trim();
+                let parsed = if key.contains('\n') {
+                    PublicKey::decode(key)
+                } else {
+                    PublicKey::from_base64(key)
+                };
+                parsed.map_err(|_| Error::InvalidPublicKey(key.to_string()))
+            })
+            .collect()
+    }
+
+    /// Verifies the file at `path` against a minisign `signature`, which must have been made by
+    /// one of the trusted keys, for a file with the same name.
+    pub async fn verify_file(&self, path: &Path, signature: &str) -> Result<()> {
+        let name = path.to_string_lossy().to_string();
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let public_keys = self.parse_public_keys()?;
+        let signature = decode_signature(signature, &name)?;
+        let mut verifier = get_stream_verifier(&public_keys, &signature, &name)?;
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            verifier.update(&buf[..n]);
+        }
+        finalize_stream_verifier(verifier, &signature, &file_name, &name)
+    }
+}
+
+/// Decodes a minisign signature for the file called `name`.
+pub(crate) fn decode_signature(signature: &str, name: &str) -> Result<Signature> {
+    Signature::decode(signature).map_err(|_| Error::SignatureInvalid(name.to_string()))
+}
+
+/// Returns a verifier that checks data against `signature` as it is fed in, using whichever of
+/// the public keys the signature was made by.
+pub(crate) fn get_stream_verifier<'a>(
+    public_keys: &'a [PublicKey],
+    signature: &'a Signature,
+    name: &str,
+) -> Result<StreamVerifier<'a>> {
+    public_keys
+        .iter()
+        .find_map(|key| key.verify_stream(signature).ok())
+        .ok_or_else(|| Error::SignatureInvalid(name.to_string()))
+}
+
+/// Completes the verification of the data fed to `verifier`, then checks that the trusted comment
+/// of `signature` names `file_name` as the signed file. `name` identifies the file in errors.
+pub(crate) fn finalize_stream_verifier(
+    mut verifier: StreamVerifier<'_>,
+    signature: &Signature,
+    file_name: &str,
+    name: &str,
+) -> Result<()> {
+    verifier
+        .finalize()
+        .map_err(|_| Error::SignatureInvalid(name.to_string()))?;
+    // The trusted comment is covered by the global signature, so it can only be relied on once
+    // the verifier has finished.
+    let signed_file_name = signature
+        .trusted_comment()
+        .split('\t')
+        .find_map(|field| field.strip_prefix("file:"));
+    if signed_file_name != Some(file_name) {
+        return Err(Error::SignatureFileMismatch(name.to_string()));
+    }
+    Ok(())
+}
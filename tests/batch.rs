@@ -0,0 +1,221 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, DownloadTarget, Platform,
+    ProgressEvent, ReleaseType,
+};
+use assert_fs::prelude::*;
+use common::{serve_bytes, MockServer, Response, ARCHIVE_CONTENTS};
+use semver::{Version, VersionReq};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        ant_base_url: server.url(),
+        antctl_base_url: server.url(),
+        antnode_base_url: server.url(),
+        crates_io_api_base_url: format!("{}/api/v1", server.url()),
+        nat_detection_base_url: server.url(),
+        ..Default::default()
+    }
+}
+
+fn provisioning_targets() -> Vec<DownloadTarget> {
+    [
+        (ReleaseType::Ant, "0.3.1"),
+        (ReleaseType::AntCtl, "0.11.3"),
+        (ReleaseType::AntCtlDaemon, "0.11.3"),
+        (ReleaseType::AntNode, "0.112.7"),
+        (ReleaseType::NatDetection, "0.2.11"),
+    ]
+    .into_iter()
+    .map(|(release_type, version)| {
+        DownloadTarget::new(
+            release_type,
+            Version::parse(version).unwrap(),
+            Platform::LinuxMusl,
+        )
+    })
+    .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn should_download_targets_concurrently_within_limit() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let in_flight = in_flight.clone();
+        let max_in_flight = max_in_flight.clone();
+        move |request| {
+            if !request.path.ends_with(".tar.gz") {
+                return Response::not_found();
+            }
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(current, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(100));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        max_concurrent_downloads: 2,
+        ..repo_for(&server)
+    };
+    let targets = provisioning_targets();
+
+    let results = release_repo
+        .download_releases(&targets, &dest_dir, &|_, _| {})
+        .await;
+
+    assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    assert_eq!(results.len(), targets.len());
+    let expected_names = [
+        "ant-0.3.1-x86_64-unknown-linux-musl.tar.gz",
+        "antctl-0.11.3-x86_64-unknown-linux-musl.tar.gz",
+        "antctld-0.11.3-x86_64-unknown-linux-musl.tar.gz",
+        "antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz",
+        "nat-detection-0.2.11-x86_64-unknown-linux-musl.tar.gz",
+    ];
+    for ((result, target), name) in results.iter().zip(&targets).zip(expected_names) {
+        assert_eq!(&result.target, target);
+        assert_eq!(result.result.as_ref().unwrap(), dest_dir.child(name).path());
+        dest_dir.child(name).assert(ARCHIVE_CONTENTS);
+    }
+}
+
+#[tokio::test]
+async fn should_return_result_for_each_target_when_one_fails() {
+    let server = MockServer::start(|request| {
+        if request.path.starts_with("/antctl-") || !request.path.ends_with(".tar.gz") {
+            Response::not_found()
+        } else {
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let targets = provisioning_targets();
+
+    let results = repo_for(&server)
+        .download_releases(&targets, &dest_dir, &|_, _| {})
+        .await;
+
+    for result in &results {
+        assert!(result.version.is_some());
+        if result.target.release_type == ReleaseType::AntCtl {
+            assert!(matches!(
+                result.result,
+                Err(Error::ReleaseBinaryNotFound(_))
+            ));
+        } else {
+            assert!(result.result.is_ok(), "{:?} failed", result.target);
+        }
+    }
+}
+
+#[tokio::test]
+async fn should_resolve_version_requirements_and_report_progress_per_target() {
+    let server = MockServer::start(|request| {
+        if request.path.starts_with("/api/v1/crates/ant-node/versions") {
+            Response::new(
+                200,
+                r#"{ "versions": [
+                    { "num": "0.112.6", "created_at": "2024-12-02T00:00:00+00:00", "yanked": false },
+                    { "num": "0.112.7", "created_at": "2024-12-12T00:00:00+00:00", "yanked": false },
+                    { "num": "0.113.0", "created_at": "2024-12-20T00:00:00+00:00", "yanked": true }
+                   ], "meta": { "next_page": null } }"#,
+            )
+        } else if request.path.ends_with(".tar.gz") || request.path.ends_with(".zip") {
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        } else {
+            Response::not_found()
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let targets = vec![
+        DownloadTarget::new(
+            ReleaseType::AntNode,
+            VersionReq::parse(">=0.112").unwrap(),
+            Platform::LinuxMusl,
+        ),
+        DownloadTarget::new(
+            ReleaseType::AntNode,
+            VersionReq::parse("^0.114").unwrap(),
+            Platform::LinuxMusl,
+        ),
+        DownloadTarget::new(
+            ReleaseType::AntCtl,
+            Version::parse("0.11.3").unwrap(),
+            Platform::Windows,
+        ),
+    ];
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let callback = {
+        let events = events.clone();
+        move |index, event| events.lock().unwrap().push((index, event))
+    };
+
+    let results = repo_for(&server)
+        .download_releases(&targets, &dest_dir, &callback)
+        .await;
+
+    assert_eq!(results[0].version, Some(Version::parse("0.112.7").unwrap()));
+    assert_eq!(
+        results[0].result.as_ref().unwrap(),
+        dest_dir
+            .child("antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz")
+            .path()
+    );
+    assert_eq!(results[1].version, None);
+    assert!(matches!(
+        results[1].result,
+        Err(Error::NoMatchingVersion { .. })
+    ));
+    assert_eq!(results[2].version, Some(Version::parse("0.11.3").unwrap()));
+    assert_eq!(
+        results[2].result.as_ref().unwrap(),
+        dest_dir
+            .child("antctl-0.11.3-x86_64-pc-windows-msvc.zip")
+            .path()
+    );
+
+    let events = events.lock().unwrap().clone();
+    let events_for = |index: usize| -> Vec<ProgressEvent> {
+        events
+            .iter()
+            .filter(|(i, _)| *i == index)
+            .map(|(_, event)| event.clone())
+            .collect()
+    };
+    let first = events_for(0);
+    assert_eq!(first.first(), Some(&ProgressEvent::ResolvingVersion));
+    assert_eq!(
+        first.last(),
+        Some(&ProgressEvent::Completed {
+            path: results[0].result.as_ref().unwrap().clone()
+        })
+    );
+    let second = events_for(1);
+    assert_eq!(second.first(), Some(&ProgressEvent::ResolvingVersion));
+    assert!(matches!(second.last(), Some(ProgressEvent::Failed { .. })));
+    let third = events_for(2);
+    assert_eq!(third.first(), Some(&ProgressEvent::Connecting));
+    assert!(matches!(
+        third.last(),
+        Some(ProgressEvent::Completed { .. })
+    ));
+}
@@ -0,0 +1,128 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ArchiveType, HttpConfig, Platform,
+    ProgressEvent, ReleaseCache, ReleaseType,
+};
+use assert_fs::prelude::*;
+use common::{serve_bytes, MockServer, Response, ARCHIVE_CONTENTS};
+use semver::Version;
+use std::collections::HashMap;
+
+#[test]
+fn should_start_from_default_configuration() {
+    let default_repo = AntReleaseRepository::default();
+
+    let built_repo = AntReleaseRepository::builder().build().unwrap();
+
+    assert_eq!(built_repo.antnode_base_url, default_repo.antnode_base_url);
+    assert_eq!(
+        built_repo.crates_io_api_base_url,
+        default_repo.crates_io_api_base_url
+    );
+    assert_eq!(built_repo.winsw_url, default_repo.winsw_url);
+    assert!(built_repo.cache.is_none());
+}
+
+#[test]
+fn should_override_base_url_of_one_release_type() {
+    let release_repo = AntReleaseRepository::builder()
+        .base_url(ReleaseType::AntCtlDaemon, "http://localhost:8080")
+        .build()
+        .unwrap();
+
+    assert_eq!(release_repo.antctl_base_url, "http://localhost:8080");
+    assert_eq!(
+        release_repo.antnode_base_url,
+        AntReleaseRepository::default().antnode_base_url
+    );
+}
+
+#[tokio::test]
+async fn should_point_every_endpoint_at_local_server() {
+    let server = MockServer::start(|request| match request.path.as_str() {
+        "/api/v1/crates/ant-node" => Response::new(
+            200,
+            r#"{ "crate": { "name": "ant-node", "newest_version": "0.112.7" } }"#,
+        ),
+        "/antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz" | "/WinSW-x64.exe" => {
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        }
+        _ => Response::not_found(),
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository::builder()
+        .all_base_urls(server.url())
+        .crates_io_api_base_url(format!("{}/api/v1", server.url()))
+        .winsw_url(format!("{}/WinSW-x64.exe", server.url()))
+        .cache(ReleaseCache::new(cache_dir.path()))
+        .http_config(HttpConfig {
+            user_agent: "provisioner/1.0".to_string(),
+            ..Default::default()
+        })
+        .build()
+        .unwrap();
+    let callback = |_: ProgressEvent| {};
+
+    let version = release_repo
+        .get_latest_version(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+    let archive_path = release_repo
+        .download_release_from_s3(
+            &ReleaseType::AntNode,
+            &version,
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            &dest_dir,
+            &callback,
+        )
+        .await
+        .unwrap();
+    let winsw_path = dest_dir.child("winsw.exe");
+    release_repo
+        .download_winsw(&winsw_path, &callback)
+        .await
+        .unwrap();
+
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+    winsw_path.assert(ARCHIVE_CONTENTS);
+    assert!(release_repo
+        .cache
+        .unwrap()
+        .get(
+            &ReleaseType::AntNode,
+            &version,
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz
+        )
+        .unwrap()
+        .is_some());
+    assert!(server
+        .requests()
+        .iter()
+        .all(|request| request.header("user-agent") == Some("provisioner/1.0")));
+}
+
+#[test]
+fn should_fail_to_build_with_invalid_http_config() {
+    let result = AntReleaseRepository::builder()
+        .http_config(HttpConfig {
+            headers: HashMap::from([("Bad Header".to_string(), "value".to_string())]),
+            ..Default::default()
+        })
+        .build();
+
+    assert!(matches!(result, Err(Error::InvalidHttpHeader(name)) if name == "Bad Header"));
+}
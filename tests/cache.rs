@@ -0,0 +1,346 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ArchiveType, CachePolicy,
+    ChecksumVerification, Platform, ProgressEvent, ReleaseCache, ReleaseType,
+};
+use assert_fs::prelude::*;
+use common::{serve_bytes, MockServer, Response, ARCHIVE_CONTENTS};
+use filetime::FileTime;
+use semver::Version;
+use std::time::{Duration, SystemTime};
+
+async fn start_server() -> MockServer {
+    MockServer::start(move |request| {
+        if request.path.ends_with(".tar.gz") {
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        } else {
+            Response::not_found()
+        }
+    })
+    .await
+}
+
+fn cached_repo(server: &MockServer, cache_dir: &assert_fs::TempDir) -> AntReleaseRepository {
+    AntReleaseRepository {
+        antnode_base_url: server.url(),
+        antctl_base_url: server.url(),
+        cache: Some(ReleaseCache::new(cache_dir.path())),
+        ..Default::default()
+    }
+}
+
+async fn download(
+    release_repo: &AntReleaseRepository,
+    release_type: &ReleaseType,
+    version: &str,
+    dest_dir: &std::path::Path,
+) -> std::path::PathBuf {
+    let progress_callback = |_event: ProgressEvent| {};
+    release_repo
+        .download_release_from_s3(
+            release_type,
+            &Version::parse(version).unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            dest_dir,
+            &progress_callback,
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn should_serve_repeated_download_from_cache() {
+    let server = start_server().await;
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = cached_repo(&server, &cache_dir);
+
+    let first_dir = assert_fs::TempDir::new().unwrap();
+    download(&release_repo, &ReleaseType::AntNode, "0.112.7", &first_dir).await;
+    let second_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = download(&release_repo, &ReleaseType::AntNode, "0.112.7", &second_dir).await;
+
+    assert_eq!(std::fs::read(&archive_path).unwrap(), ARCHIVE_CONTENTS);
+    assert_eq!(
+        archive_path,
+        second_dir.join("antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz")
+    );
+    let archive_requests = server.requests_for("/antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz");
+    assert_eq!(archive_requests.len(), 1);
+}
+
+#[tokio::test]
+async fn should_not_serve_cached_archive_once_verification_is_stricter() {
+    let server = start_server().await;
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    download(
+        &cached_repo(&server, &cache_dir),
+        &ReleaseType::AntNode,
+        "0.112.7",
+        &dest_dir,
+    )
+    .await;
+    let strict_repo = AntReleaseRepository {
+        checksum_verification: ChecksumVerification::Required,
+        ..cached_repo(&server, &cache_dir)
+    };
+
+    let progress_callback = |_event: ProgressEvent| {};
+    let result = strict_repo
+        .download_release_from_s3(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            &assert_fs::TempDir::new().unwrap(),
+            &progress_callback,
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::ChecksumMissing(_))));
+}
+
+#[tokio::test]
+async fn should_not_share_cache_entries_between_versions() {
+    let server = start_server().await;
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = cached_repo(&server, &cache_dir);
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download(&release_repo, &ReleaseType::AntNode, "0.112.6", &dest_dir).await;
+    download(&release_repo, &ReleaseType::AntNode, "0.112.7", &dest_dir).await;
+
+    assert_eq!(
+        server
+            .requests_for("/antnode-0.112.6-x86_64-unknown-linux-musl.tar.gz")
+            .len(),
+        1
+    );
+    assert_eq!(
+        server
+            .requests_for("/antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz")
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn should_list_size_and_clear_cache() {
+    let server = start_server().await;
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = cached_repo(&server, &cache_dir);
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download(&release_repo, &ReleaseType::AntNode, "0.112.7", &dest_dir).await;
+    download(&release_repo, &ReleaseType::AntCtl, "0.11.4", &dest_dir).await;
+
+    let cache = release_repo.cache.as_ref().unwrap();
+    let mut entries = cache.list().unwrap();
+    entries.sort_by_key(|entry| entry.release_type.to_string());
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].release_type, ReleaseType::AntCtl);
+    assert_eq!(entries[0].version, Version::parse("0.11.4").unwrap());
+    assert_eq!(entries[1].release_type, ReleaseType::AntNode);
+    assert_eq!(entries[1].platform, Platform::LinuxMusl);
+    assert_eq!(entries[1].archive_type, ArchiveType::TarGz);
+    assert_eq!(entries[1].size, ARCHIVE_CONTENTS.len() as u64);
+
+    // Both archives have identical content, so they share a single blob.
+    assert_eq!(entries[0].sha256, entries[1].sha256);
+    assert_eq!(cache.size().unwrap(), ARCHIVE_CONTENTS.len() as u64);
+
+    cache.clear().unwrap();
+    assert!(cache.list().unwrap().is_empty());
+    assert_eq!(cache.size().unwrap(), 0);
+}
+
+#[tokio::test]
+async fn should_insert_and_get_archive_directly() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let archive = cache_dir.child("archive.zip");
+    archive.write_binary(b"zip archive").unwrap();
+    let cache = ReleaseCache::new(cache_dir.child("cache").path());
+    let version = Version::parse("0.4.6-rc.1").unwrap();
+
+    assert!(cache
+        .get(
+            &ReleaseType::NodeLaunchpad,
+            &version,
+            &Platform::Windows,
+            &ArchiveType::Zip
+        )
+        .unwrap()
+        .is_none());
+
+    let inserted = cache
+        .insert(
+            &ReleaseType::NodeLaunchpad,
+            &version,
+            &Platform::Windows,
+            &ArchiveType::Zip,
+            &archive,
+            "checksum=required;signature=disabled",
+        )
+        .unwrap();
+    let entry = cache
+        .get(
+            &ReleaseType::NodeLaunchpad,
+            &version,
+            &Platform::Windows,
+            &ArchiveType::Zip,
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(inserted, entry);
+    assert_eq!(entry.verified_with, "checksum=required;signature=disabled");
+    assert_eq!(std::fs::read(&entry.path).unwrap(), b"zip archive");
+}
+
+/// Inserts an archive with the given content into the cache, recording it as having been cached
+/// `age_secs` seconds ago.
+fn insert_aged(
+    cache: &ReleaseCache,
+    release_type: &ReleaseType,
+    version: &str,
+    content: &[u8],
+    age_secs: u64,
+) {
+    let archive = assert_fs::NamedTempFile::new("archive.tar.gz").unwrap();
+    archive.write_binary(content).unwrap();
+    cache
+        .insert(
+            release_type,
+            &Version::parse(version).unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            &archive,
+            "",
+        )
+        .unwrap();
+
+    let index_path = cache
+        .root()
+        .join("index")
+        .join(release_type.to_string())
+        .join(version)
+        .join("x86_64-unknown-linux-musl.tar.gz");
+    let cached_at = SystemTime::now() - Duration::from_secs(age_secs);
+    filetime::set_file_mtime(index_path, FileTime::from_system_time(cached_at)).unwrap();
+}
+
+fn cached_versions(cache: &ReleaseCache, release_type: &ReleaseType) -> Vec<String> {
+    let mut versions: Vec<String> = cache
+        .list()
+        .unwrap()
+        .into_iter()
+        .filter(|entry| entry.release_type == *release_type)
+        .map(|entry| entry.version.to_string())
+        .collect();
+    versions.sort();
+    versions
+}
+
+#[test]
+fn should_prune_entries_older_than_max_age() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let cache = ReleaseCache::new(cache_dir.path()).with_policy(CachePolicy {
+        max_age: Some(Duration::from_secs(3600)),
+        ..Default::default()
+    });
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.5", b"old", 7200);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.6", b"new", 60);
+
+    let removed = cache.prune().unwrap();
+
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].version, Version::parse("0.112.5").unwrap());
+    assert!(!removed[0].path.exists());
+    assert_eq!(cached_versions(&cache, &ReleaseType::AntNode), ["0.112.6"]);
+}
+
+#[test]
+fn should_keep_last_n_versions_per_release_type() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let cache = ReleaseCache::new(cache_dir.path()).with_policy(CachePolicy {
+        keep_last_versions: Some(2),
+        ..Default::default()
+    });
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.7-rc.1", b"a", 0);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.5", b"b", 0);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.7", b"c", 0);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.6", b"d", 0);
+    insert_aged(&cache, &ReleaseType::AntCtl, "0.11.3", b"e", 0);
+
+    let removed = cache.prune().unwrap();
+
+    assert_eq!(removed.len(), 2);
+    assert_eq!(
+        cached_versions(&cache, &ReleaseType::AntNode),
+        ["0.112.7", "0.112.7-rc.1"]
+    );
+    assert_eq!(cached_versions(&cache, &ReleaseType::AntCtl), ["0.11.3"]);
+}
+
+#[test]
+fn should_evict_oldest_entries_to_stay_within_max_total_size() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let cache = ReleaseCache::new(cache_dir.path()).with_policy(CachePolicy {
+        max_total_size: Some(25),
+        ..Default::default()
+    });
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.5", &[1; 10], 300);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.6", &[2; 10], 200);
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.7", &[3; 10], 100);
+    // Shares its blob with 0.112.7, so takes up no extra space.
+    insert_aged(&cache, &ReleaseType::AntCtl, "0.11.4", &[3; 10], 400);
+    assert_eq!(cache.size().unwrap(), 30);
+
+    let removed = cache.prune().unwrap();
+
+    assert_eq!(removed.len(), 2);
+    assert_eq!(cache.size().unwrap(), 20);
+    assert_eq!(
+        cached_versions(&cache, &ReleaseType::AntNode),
+        ["0.112.6", "0.112.7"]
+    );
+    assert!(cached_versions(&cache, &ReleaseType::AntCtl).is_empty());
+}
+
+#[test]
+fn should_not_prune_anything_with_default_policy() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let cache = ReleaseCache::new(cache_dir.path());
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.5", b"old", 1_000_000);
+
+    assert!(cache.prune().unwrap().is_empty());
+    assert_eq!(cached_versions(&cache, &ReleaseType::AntNode), ["0.112.5"]);
+}
+
+#[test]
+fn should_keep_evicted_archive_readable_where_it_was_already_linked() {
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    let cache = ReleaseCache::new(cache_dir.child("cache").path()).with_policy(CachePolicy {
+        max_age: Some(Duration::from_secs(1)),
+        ..Default::default()
+    });
+    insert_aged(&cache, &ReleaseType::AntNode, "0.112.5", b"archive", 10);
+    let entry = cache.list().unwrap().remove(0);
+    let linked = cache_dir.child("antnode.tar.gz");
+    cache.copy_to(&entry, &linked).unwrap();
+
+    cache.prune().unwrap();
+
+    assert!(!entry.path.exists());
+    assert_eq!(std::fs::read(linked.path()).unwrap(), b"archive");
+}
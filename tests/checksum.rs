@@ -0,0 +1,175 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ChecksumVerification, ProgressEvent,
+};
+use assert_fs::prelude::*;
+use common::{
+    download_antnode, etag, sha256_hex, start_archive_server, ARCHIVE_CONTENTS, ARCHIVE_NAME,
+};
+
+#[tokio::test]
+async fn should_verify_archive_against_published_checksum() {
+    let checksum = format!("{}  {ARCHIVE_NAME}\n", sha256_hex(ARCHIVE_CONTENTS));
+    let server = start_archive_server(Some(checksum), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let archive_path = download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+}
+
+#[tokio::test]
+async fn should_delete_archive_and_fail_when_checksum_does_not_match() {
+    let wrong_checksum = sha256_hex(b"something else entirely");
+    let server = start_archive_server(Some(wrong_checksum.clone()), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let result = download_antnode(&release_repo, &dest_dir).await;
+
+    match result {
+        Err(Error::ChecksumMismatch { expected, actual }) => {
+            assert_eq!(expected, wrong_checksum);
+            assert_eq!(actual, sha256_hex(ARCHIVE_CONTENTS));
+        }
+        other => panic!("Expected ChecksumMismatch error, got {other:?}"),
+    }
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::missing());
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part"))
+        .assert(predicates::path::missing());
+    assert_eq!(server.requests_for(&format!("/{ARCHIVE_NAME}")).len(), 1);
+}
+
+#[tokio::test]
+async fn should_verify_checksum_over_resumed_download() {
+    let server = start_archive_server(Some(sha256_hex(ARCHIVE_CONTENTS)), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part"))
+        .write_binary(&ARCHIVE_CONTENTS[..10])
+        .unwrap();
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part.validator"))
+        .write_str(&etag(ARCHIVE_CONTENTS))
+        .unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests[0].header("range"), Some("bytes=10-"));
+}
+
+#[tokio::test]
+async fn should_skip_verification_when_no_checksum_is_published() {
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::is_file());
+}
+
+#[tokio::test]
+async fn should_fail_when_checksum_is_required_but_not_published() {
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        checksum_verification: ChecksumVerification::Required,
+        ..Default::default()
+    };
+
+    let result = download_antnode(&release_repo, &dest_dir).await;
+
+    assert!(matches!(result, Err(Error::ChecksumMissing(_))));
+    assert!(server.requests_for(&format!("/{ARCHIVE_NAME}")).is_empty());
+}
+
+#[tokio::test]
+async fn should_not_fetch_checksum_when_verification_is_disabled() {
+    let server = start_archive_server(Some(sha256_hex(b"wrong")), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        checksum_verification: ChecksumVerification::Disabled,
+        ..Default::default()
+    };
+
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert!(server
+        .requests_for(&format!("/{ARCHIVE_NAME}.sha256"))
+        .is_empty());
+}
+
+#[tokio::test]
+async fn should_verify_custom_url_against_caller_supplied_checksum() {
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository::default();
+    let progress_callback = |_event: ProgressEvent| {};
+    let url = format!("{}/{}", server.url(), ARCHIVE_NAME);
+
+    let archive_path = release_repo
+        .download_release_with_checksum(
+            &url,
+            &sha256_hex(ARCHIVE_CONTENTS).to_uppercase(),
+            &dest_dir,
+            &progress_callback,
+        )
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+
+    let result = release_repo
+        .download_release_with_checksum(&url, &sha256_hex(b"wrong"), &dest_dir, &progress_callback)
+        .await;
+    assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+}
+
+#[tokio::test]
+async fn should_reject_malformed_caller_supplied_checksum() {
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository::default();
+    let progress_callback = |_event: ProgressEvent| {};
+
+    let result = release_repo
+        .download_release_with_checksum(
+            "http://127.0.0.1:1/antnode.tar.gz",
+            "not-a-digest",
+            &dest_dir,
+            &progress_callback,
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidChecksum(_))));
+}
@@ -0,0 +1,453 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! A minimal HTTP/1.1 server used as a local stand-in for S3 and the other services the release
+//! repository talks to, along with the archive fixtures the tests download through it.
+
+#![allow(dead_code)]
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ArchiveType, Platform,
+    ProgressCallback, ProgressEvent, ReleaseType,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blake2::Blake2b512;
+use ed25519_dalek::{Signer, SigningKey};
+use semver::Version;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(&name.to_lowercase()).map(|v| v.as_str())
+    }
+
+    /// Returns the path without its query string.
+    pub fn path_only(&self) -> &str {
+        self.path.split('?').next().unwrap_or_default()
+    }
+
+    /// Returns the percent-decoded value of a query string parameter.
+    pub fn query_param(&self, name: &str) -> Option<String> {
+        let (_, query) = self.path.split_once('?')?;
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key == name).then(|| percent_decode(value))
+        })
+    }
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+                decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            b => {
+                decoded.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap()
+}
+
+#[derive(Clone, Debug)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// When set, the connection is closed after sending this many bytes of the body, even though
+    /// the `Content-Length` header advertises all of it.
+    pub truncate_at: Option<usize>,
+}
+
+impl Response {
+    pub fn new(status: u16, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            truncate_at: None,
+        }
+    }
+
+    /// A response that uses chunked transfer encoding, so it has no `Content-Length` header.
+    pub fn chunked(status: u16, body: &[u8]) -> Self {
+        let mut encoded = Vec::new();
+        for chunk in body.chunks(1024) {
+            encoded.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            encoded.extend_from_slice(chunk);
+            encoded.extend_from_slice(b"\r\n");
+        }
+        encoded.extend_from_slice(b"0\r\n\r\n");
+        Self::new(status, encoded).with_header("Transfer-Encoding", "chunked")
+    }
+
+    pub fn not_found() -> Self {
+        Self::new(404, "Not Found")
+    }
+
+    pub fn truncated(mut self, len: usize) -> Self {
+        self.truncate_at = Some(len);
+        self
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+type Handler = dyn Fn(&Request) -> Response + Send + Sync;
+
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl MockServer {
+    pub async fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+
+        let requests_clone = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    break;
+                };
+                let handler = handler.clone();
+                let requests = requests_clone.clone();
+                tokio::spawn(async move {
+                    let _ = handle_connection(stream, handler, requests).await;
+                });
+            }
+        });
+
+        Self { addr, requests }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Returns the requests that were made for the given path, ignoring any query string.
+    pub fn requests_for(&self, path: &str) -> Vec<Request> {
+        self.requests()
+            .into_iter()
+            .filter(|r| r.path_only() == path)
+            .collect()
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<Request>>>,
+) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&buf).to_string();
+    let mut lines = head.split("\r\n");
+    let request_line = lines.next().unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+    let mut headers = HashMap::new();
+    for line in lines.take_while(|l| !l.is_empty()) {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let request = Request {
+        method,
+        path,
+        headers,
+    };
+    requests.lock().unwrap().push(request.clone());
+    let response = handler(&request);
+
+    let mut out = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    let mut has_content_length = false;
+    for (name, value) in &response.headers {
+        if name.eq_ignore_ascii_case("content-length")
+            || name.eq_ignore_ascii_case("transfer-encoding")
+        {
+            has_content_length = true;
+        }
+        out.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if !has_content_length {
+        out.push_str(&format!("Content-Length: {}\r\n", response.body.len()));
+    }
+    out.push_str("Connection: close\r\n\r\n");
+
+    stream.write_all(out.as_bytes()).await?;
+    let body_len = response
+        .truncate_at
+        .unwrap_or(response.body.len())
+        .min(response.body.len());
+    stream.write_all(&response.body[..body_len]).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Serve `body`, honouring a `Range: bytes=N-` request header if `supports_ranges` is set. The
+/// response carries the `etag` of the body, and the range is ignored if an `If-Range` header
+/// names a different one.
+pub fn serve_bytes(request: &Request, body: &[u8], supports_ranges: bool) -> Response {
+    let etag = etag(body);
+    let unchanged = request
+        .header("if-range")
+        .is_none_or(|validator| validator == etag);
+    if supports_ranges && unchanged {
+        if let Some(start) = request
+            .header("range")
+            .and_then(|r| r.strip_prefix("bytes="))
+            .and_then(|r| r.strip_suffix('-'))
+            .and_then(|r| r.parse::<usize>().ok())
+        {
+            if start >= body.len() {
+                return Response::new(416, "")
+                    .with_header("Content-Range", &format!("bytes */{}", body.len()));
+            }
+            return Response::new(206, body[start..].to_vec())
+                .with_header(
+                    "Content-Range",
+                    &format!("bytes {}-{}/{}", start, body.len() - 1, body.len()),
+                )
+                .with_header("ETag", &etag);
+        }
+    }
+    Response::new(200, body.to_vec()).with_header("ETag", &etag)
+}
+
+/// The entity tag `serve_bytes` gives `body`.
+pub fn etag(body: &[u8]) -> String {
+    format!("\"{:x}\"", Sha256::digest(body))
+}
+
+/// Builds the XML body of an S3 `ListObjectsV2` response listing `objects`, each given as a
+/// (key, size, last modified) tuple.
+pub fn list_objects_xml(objects: &[(&str, u64, &str)], next_token: Option<&str>) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>bucket</Name>
+"#,
+    );
+    xml.push_str(&format!("  <KeyCount>{}</KeyCount>\n", objects.len()));
+    xml.push_str(&format!(
+        "  <IsTruncated>{}</IsTruncated>\n",
+        next_token.is_some()
+    ));
+    if let Some(token) = next_token {
+        xml.push_str(&format!(
+            "  <NextContinuationToken>{token}</NextContinuationToken>\n"
+        ));
+    }
+    for (key, size, last_modified) in objects {
+        xml.push_str(&format!(
+            "  <Contents>\n    <Key>{key}</Key>\n    <LastModified>{last_modified}</LastModified>\n    \
+             <ETag>&quot;abc&quot;</ETag>\n    <Size>{size}</Size>\n    \
+             <StorageClass>STANDARD</StorageClass>\n  </Contents>\n"
+        ));
+    }
+    xml.push_str("</ListBucketResult>\n");
+    xml
+}
+
+/// A minisign key pair, built from a fixed seed so the tests are deterministic.
+pub struct TestKey {
+    key_id: [u8; 8],
+    signing_key: SigningKey,
+}
+
+impl TestKey {
+    pub fn new(seed: u8) -> Self {
+        Self {
+            key_id: [seed; 8],
+            signing_key: SigningKey::from_bytes(&[seed; 32]),
+        }
+    }
+
+    pub fn public_key(&self) -> String {
+        let mut bin = b"Ed".to_vec();
+        bin.extend_from_slice(&self.key_id);
+        bin.extend_from_slice(self.signing_key.verifying_key().as_bytes());
+        STANDARD.encode(bin)
+    }
+
+    /// Produces a pre-hashed minisign signature of `data`, as made by current versions of minisign
+    /// when signing a file called `file_name`.
+    pub fn sign(&self, file_name: &str, data: &[u8]) -> String {
+        let signature = self.signing_key.sign(&Blake2b512::digest(data)).to_bytes();
+        let mut bin = b"ED".to_vec();
+        bin.extend_from_slice(&self.key_id);
+        bin.extend_from_slice(&signature);
+
+        let trusted_comment = format!("timestamp:1700000000\tfile:{file_name}\thashed");
+        let mut global = signature.to_vec();
+        global.extend_from_slice(trusted_comment.as_bytes());
+        let global_signature = self.signing_key.sign(&global).to_bytes();
+
+        format!(
+            "untrusted comment: signature from minisign secret key\n{}\ntrusted comment: {}\n{}\n",
+            STANDARD.encode(bin),
+            trusted_comment,
+            STANDARD.encode(global_signature)
+        )
+    }
+}
+
+/// The name of the antnode archive the tests download.
+pub const ARCHIVE_NAME: &str = "antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz";
+/// Stand-in contents for the archive, for tests that don't extract it.
+pub const ARCHIVE_CONTENTS: &[u8] = b"pretend this is a gzipped tarball";
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Starts a server hosting `ARCHIVE_CONTENTS` as `ARCHIVE_NAME`, along with its checksum and
+/// signature sidecars if provided.
+pub async fn start_archive_server(
+    checksum: Option<String>,
+    signature: Option<String>,
+) -> MockServer {
+    MockServer::start(move |request| {
+        if request.path == format!("/{ARCHIVE_NAME}") {
+            return serve_bytes(request, ARCHIVE_CONTENTS, true);
+        }
+        let sidecar = if request.path == format!("/{ARCHIVE_NAME}.sha256") {
+            checksum.as_ref()
+        } else if request.path == format!("/{ARCHIVE_NAME}.sig") {
+            signature.as_ref()
+        } else {
+            None
+        };
+        match sidecar {
+            Some(contents) => Response::new(200, contents.clone()),
+            None => Response::not_found(),
+        }
+    })
+    .await
+}
+
+/// Downloads the antnode archive from S3 (or the repository's mirrors) into `dest_dir`.
+pub async fn download_antnode(
+    release_repo: &AntReleaseRepository,
+    dest_dir: &Path,
+) -> Result<PathBuf, Error> {
+    download_antnode_with_progress(release_repo, dest_dir, &|_event: ProgressEvent| {}).await
+}
+
+pub async fn download_antnode_with_progress(
+    release_repo: &AntReleaseRepository,
+    dest_dir: &Path,
+    progress_callback: &ProgressCallback,
+) -> Result<PathBuf, Error> {
+    release_repo
+        .download_release_from_s3(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            dest_dir,
+            progress_callback,
+        )
+        .await
+}
+
+/// Writes an uncompressed tarball of the entries, given as (name, contents) pairs, to `writer`,
+/// returning the writer. Names ending in `/` are directories.
+pub fn write_tar<W: Write>(writer: W, entries: &[(&str, &[u8])]) -> W {
+    let mut builder = tar::Builder::new(writer);
+    for (name, contents) in entries {
+        let mut header = tar::Header::new_gnu();
+        if name.ends_with('/') {
+            header.set_entry_type(tar::EntryType::Directory);
+        }
+        header.set_mode(0o755);
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, name, *contents).unwrap();
+    }
+    builder.into_inner().unwrap()
+}
+
+pub fn create_tar_gz(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    write_tar(encoder, entries).finish().unwrap()
+}
+
+pub fn create_zip(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    create_zip_with_options(entries, zip::write::FileOptions::default())
+}
+
+/// Builds a zip archive of the entries, each written with `options`. Names ending in `/` are
+/// directories.
+pub fn create_zip_with_options(
+    entries: &[(&str, &[u8])],
+    options: zip::write::FileOptions,
+) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, contents) in entries {
+        if name.ends_with('/') {
+            writer.add_directory(*name, options).unwrap();
+        } else {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
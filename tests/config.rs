@@ -0,0 +1,398 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_releases::{error::Error, AntReleaseRepository, ReleaseType, RepositoryConfig};
+use assert_fs::prelude::*;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The environment is shared by every test in the process, so tests that use it take turns.
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// Runs `f` with exactly the given `ANT_RELEASES_*` variables set.
+fn with_env<T>(vars: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+    let vars: Vec<(&str, &OsStr)> = vars
+        .iter()
+        .map(|(name, value)| (*name, OsStr::new(value)))
+        .collect();
+    with_os_env(&vars, f)
+}
+
+/// Like `with_env`, but the values needn't be valid UTF-8.
+fn with_os_env<T>(vars: &[(&str, &OsStr)], f: impl FnOnce() -> T) -> T {
+    let _guard = ENV_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    for (name, _) in std::env::vars_os() {
+        if name.to_string_lossy().starts_with("ANT_RELEASES_") {
+            std::env::remove_var(name);
+        }
+    }
+    for (name, value) in vars {
+        std::env::set_var(name, value);
+    }
+    let result = f();
+    for (name, _) in vars {
+        std::env::remove_var(name);
+    }
+    result
+}
+
+fn write_config(dir: &assert_fs::TempDir, contents: &str) -> assert_fs::fixture::ChildPath {
+    let config_file = dir.child("releases.toml");
+    config_file.write_str(contents).unwrap();
+    config_file
+}
+
+#[test]
+fn should_read_overrides_from_config_file() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(
+        &dir,
+        r#"
+base_url = "https://mirror.internal/releases/"
+cache_dir = "/var/cache/ant-releases"
+crates_io_url = "https://mirror.internal/crates-io/api/v1"
+max_concurrent_downloads = 8
+
+[urls]
+antnode = "https://mirror.internal/antnode"
+
+[http]
+connect_timeout_secs = 10
+proxy = "http://proxy.internal:3128"
+
+[http.headers]
+X-Mirror-Token = "secret"
+"#,
+    );
+
+    let config = RepositoryConfig::from_file(&config_file).unwrap();
+    assert_eq!(
+        config.base_url.as_deref(),
+        Some("https://mirror.internal/releases")
+    );
+    assert_eq!(
+        config
+            .release_urls
+            .get(&ReleaseType::AntNode)
+            .map(String::as_str),
+        Some("https://mirror.internal/antnode")
+    );
+    assert_eq!(config.connect_timeout, Some(Duration::from_secs(10)));
+    assert_eq!(config.headers.get("X-Mirror-Token").unwrap(), "secret");
+
+    let release_repo = with_env(&[], || {
+        AntReleaseRepository::from_config_file(&config_file).unwrap()
+    });
+    assert_eq!(
+        release_repo.antnode_base_url,
+        "https://mirror.internal/antnode"
+    );
+    assert_eq!(
+        release_repo.antctl_base_url,
+        "https://mirror.internal/releases"
+    );
+    assert_eq!(
+        release_repo.crates_io_api_base_url,
+        "https://mirror.internal/crates-io/api/v1"
+    );
+    assert_eq!(release_repo.max_concurrent_downloads, 8);
+    assert_eq!(
+        release_repo.cache.unwrap().root(),
+        Path::new("/var/cache/ant-releases")
+    );
+}
+
+#[test]
+fn should_read_overrides_from_env() {
+    let release_repo = with_env(
+        &[
+            ("ANT_RELEASES_ANTNODE_URL", "http://localhost:8080/antnode"),
+            (
+                "ANT_RELEASES_NAT_DETECTION_URL",
+                "http://localhost:8080/nat",
+            ),
+            ("ANT_RELEASES_CRATES_IO_URL", "http://localhost:8080/api/v1"),
+            ("ANT_RELEASES_CACHE_DIR", "/tmp/ant-releases"),
+            ("ANT_RELEASES_PROXY", "http://proxy.internal:3128"),
+        ],
+        || AntReleaseRepository::from_env().unwrap(),
+    );
+
+    let default_repo = AntReleaseRepository::default();
+    assert_eq!(
+        release_repo.antnode_base_url,
+        "http://localhost:8080/antnode"
+    );
+    assert_eq!(
+        release_repo.nat_detection_base_url,
+        "http://localhost:8080/nat"
+    );
+    assert_eq!(release_repo.antctl_base_url, default_repo.antctl_base_url);
+    assert_eq!(
+        release_repo.crates_io_api_base_url,
+        "http://localhost:8080/api/v1"
+    );
+    assert_eq!(
+        release_repo.cache.unwrap().root(),
+        Path::new("/tmp/ant-releases")
+    );
+}
+
+#[test]
+fn should_take_env_over_config_file_over_defaults() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(
+        &dir,
+        r#"
+crates_io_url = "https://file.internal/api/v1"
+winsw_url = "https://file.internal/WinSW-x64.exe"
+"#,
+    );
+
+    let release_repo = with_env(
+        &[("ANT_RELEASES_CRATES_IO_URL", "https://env.internal/api/v1")],
+        || AntReleaseRepository::from_config_file(&config_file).unwrap(),
+    );
+
+    // Set in both, so the environment wins.
+    assert_eq!(
+        release_repo.crates_io_api_base_url,
+        "https://env.internal/api/v1"
+    );
+    // Only set in the file.
+    assert_eq!(
+        release_repo.winsw_url,
+        "https://file.internal/WinSW-x64.exe"
+    );
+    // Set in neither, so the default is kept.
+    assert_eq!(
+        release_repo.antnode_base_url,
+        AntReleaseRepository::default().antnode_base_url
+    );
+}
+
+#[test]
+fn should_read_mirrors_from_config_file_and_env() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(
+        &dir,
+        r#"
+mirror_cool_down_secs = 600
+
+[mirrors]
+antnode = ["https://mirror-2.internal/antnode/", "https://mirror-3.internal/antnode"]
+antctl = ["https://mirror-2.internal/antctl"]
+"#,
+    );
+
+    let release_repo = with_env(
+        &[(
+            "ANT_RELEASES_ANTNODE_MIRRORS",
+            "https://mirror-4.internal/antnode, https://mirror-5.internal/antnode",
+        )],
+        || AntReleaseRepository::from_config_file(&config_file).unwrap(),
+    );
+
+    assert_eq!(
+        release_repo.mirrors[&ReleaseType::AntNode],
+        vec![
+            "https://mirror-4.internal/antnode",
+            "https://mirror-5.internal/antnode"
+        ]
+    );
+    assert_eq!(
+        release_repo.mirrors[&ReleaseType::AntCtlDaemon],
+        vec!["https://mirror-2.internal/antctl"]
+    );
+    assert_eq!(release_repo.mirror_cool_down, Duration::from_secs(600));
+
+    let config = RepositoryConfig::from_file(&config_file).unwrap();
+    assert_eq!(
+        config.release_mirrors[&ReleaseType::AntNode],
+        vec![
+            "https://mirror-2.internal/antnode",
+            "https://mirror-3.internal/antnode"
+        ]
+    );
+}
+
+#[test]
+fn should_point_every_release_type_at_base_url_from_env() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(
+        &dir,
+        r#"
+[urls]
+antnode = "https://file.internal/antnode"
+"#,
+    );
+
+    let release_repo = with_env(
+        &[("ANT_RELEASES_BASE_URL", "https://env.internal/releases")],
+        || AntReleaseRepository::from_config_file(&config_file).unwrap(),
+    );
+
+    for release_type in ReleaseType::all() {
+        let base_url = match release_type {
+            ReleaseType::Ant => &release_repo.ant_base_url,
+            ReleaseType::AntCtl | ReleaseType::AntCtlDaemon => &release_repo.antctl_base_url,
+            ReleaseType::AntNode => &release_repo.antnode_base_url,
+            ReleaseType::AntNodeRpcClient => &release_repo.antnode_rpc_client_base_url,
+            ReleaseType::NatDetection => &release_repo.nat_detection_base_url,
+            ReleaseType::NodeLaunchpad => &release_repo.node_launchpad_base_url,
+        };
+        assert_eq!(base_url, "https://env.internal/releases", "{release_type}");
+    }
+}
+
+#[test]
+fn should_reject_different_urls_for_antctl_and_antctld() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(
+        &dir,
+        r#"
+[urls]
+antctl = "https://mirror-1.internal/antctl"
+antctld = "https://mirror-2.internal/antctl"
+"#,
+    );
+    match RepositoryConfig::from_file(&config_file) {
+        Err(Error::InvalidConfigValue { key, .. }) => {
+            assert!(key == "urls.antctl" || key == "urls.antctld", "{key}")
+        }
+        other => panic!("Expected InvalidConfigValue, got {other:?}"),
+    }
+
+    let result = with_env(
+        &[
+            (
+                "ANT_RELEASES_ANTCTL_URL",
+                "https://mirror-1.internal/antctl",
+            ),
+            (
+                "ANT_RELEASES_ANTCTLD_URL",
+                "https://mirror-2.internal/antctl",
+            ),
+        ],
+        RepositoryConfig::from_env,
+    );
+    match result {
+        Err(Error::InvalidConfigValue { key, .. }) => assert!(
+            key == "ANT_RELEASES_ANTCTL_URL" || key == "ANT_RELEASES_ANTCTLD_URL",
+            "{key}"
+        ),
+        other => panic!("Expected InvalidConfigValue, got {other:?}"),
+    }
+
+    // The same URL for both is fine, as is overriding one of them from the environment.
+    let config_file = write_config(
+        &dir,
+        r#"
+[urls]
+antctl = "https://mirror-1.internal/antctl"
+antctld = "https://mirror-1.internal/antctl"
+"#,
+    );
+    let release_repo = with_env(
+        &[(
+            "ANT_RELEASES_ANTCTLD_URL",
+            "https://mirror-2.internal/antctl",
+        )],
+        || AntReleaseRepository::from_config_file(&config_file).unwrap(),
+    );
+    assert_eq!(
+        release_repo.antctl_base_url,
+        "https://mirror-2.internal/antctl"
+    );
+}
+
+#[test]
+fn should_name_offending_key_in_config_file() {
+    let dir = assert_fs::TempDir::new().unwrap();
+
+    let cases = [
+        (
+            "[http]\nconnect_timeout_secs = \"soon\"",
+            "http.connect_timeout_secs",
+        ),
+        (
+            "[urls]\nantnod = \"https://mirror.internal\"",
+            "urls.antnod",
+        ),
+        (
+            "winsw_url = \"ftp://mirror.internal/WinSW.exe\"",
+            "winsw_url",
+        ),
+        ("max_concurrent_downloads = 0", "max_concurrent_downloads"),
+        ("cache_dir = true", "cache_dir"),
+        ("[mirrors]\nantnode = [1, 2]", "mirrors.antnode"),
+        ("[mirrors]\nantnode = [\"not a url\"]", "mirrors.antnode"),
+    ];
+    for (contents, expected_key) in cases {
+        let config_file = write_config(&dir, contents);
+        match RepositoryConfig::from_file(&config_file) {
+            Err(Error::InvalidConfigValue { key, .. }) => assert_eq!(key, expected_key),
+            other => panic!("Expected InvalidConfigValue for {expected_key}, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn should_name_offending_env_var() {
+    let cases = [
+        ("ANT_RELEASES_ANTNODE_URL", "not a url"),
+        ("ANT_RELEASES_READ_TIMEOUT_SECS", "-1"),
+        ("ANT_RELEASES_ANTNOD_URL", "https://mirror.internal"),
+    ];
+    for (name, value) in cases {
+        let result = with_env(&[(name, value)], RepositoryConfig::from_env);
+        match result {
+            Err(Error::InvalidConfigValue { key, .. }) => assert_eq!(key, name),
+            other => panic!("Expected InvalidConfigValue for {name}, got {other:?}"),
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn should_only_reject_non_utf8_values_of_own_env_vars() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let non_utf8 = OsStr::from_bytes(b"https://mirror\xff.internal");
+    let config = with_os_env(
+        &[("UNRELATED_TO_ANT_RELEASES", non_utf8)],
+        RepositoryConfig::from_env,
+    );
+    assert!(config.is_ok(), "{config:?}");
+
+    let result = with_os_env(
+        &[("ANT_RELEASES_ANTNODE_URL", non_utf8)],
+        RepositoryConfig::from_env,
+    );
+    match result {
+        Err(Error::InvalidConfigValue { key, .. }) => assert_eq!(key, "ANT_RELEASES_ANTNODE_URL"),
+        other => panic!("Expected InvalidConfigValue, got {other:?}"),
+    }
+}
+
+#[test]
+fn should_fail_to_read_config_file_that_is_not_toml() {
+    let dir = assert_fs::TempDir::new().unwrap();
+    let config_file = write_config(&dir, "base_url = ");
+
+    let result = RepositoryConfig::from_file(&config_file);
+
+    match result {
+        Err(Error::ConfigParseError(message)) => {
+            assert!(message.contains("releases.toml"), "{message}")
+        }
+        other => panic!("Expected ConfigParseError, got {other:?}"),
+    }
+}
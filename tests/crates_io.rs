@@ -0,0 +1,301 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, Channel, ReleaseType,
+};
+use chrono::{TimeZone, Utc};
+use common::{MockServer, Response};
+use semver::{Version, VersionReq};
+use std::collections::HashMap;
+
+const FIRST_PAGE: &str = r#"{
+  "versions": [
+    { "num": "0.113.0-rc.1", "created_at": "2024-12-14T10:00:00+00:00", "yanked": false },
+    { "num": "0.112.7-rc.1", "created_at": "2024-12-10T10:00:00.000000+00:00", "yanked": false },
+    { "num": "0.112.6", "created_at": "2024-12-02T09:30:00.123456+00:00", "yanked": false }
+  ],
+  "meta": { "total": 5, "next_page": "?per_page=100&seek=abc" }
+}"#;
+
+const SECOND_PAGE: &str = r#"{
+  "versions": [
+    { "num": "0.112.5", "created_at": "2024-11-20T08:00:00+00:00", "yanked": true },
+    { "num": "0.112.7", "created_at": "2024-12-12T12:00:00+00:00", "yanked": false }
+  ],
+  "meta": { "total": 5, "next_page": null }
+}"#;
+
+/// Starts a stand-in for the crates.io API hosting the `ant-node` crate.
+async fn start_crates_io() -> MockServer {
+    MockServer::start(|request| match request.path.as_str() {
+        "/api/v1/crates/ant-node" => Response::new(
+            200,
+            r#"{ "crate": { "name": "ant-node", "newest_version": "0.112.7" } }"#,
+        ),
+        "/api/v1/crates/ant-node/versions?per_page=100" => Response::new(200, FIRST_PAGE),
+        "/api/v1/crates/ant-node/versions?per_page=100&seek=abc" => Response::new(200, SECOND_PAGE),
+        _ => Response::not_found(),
+    })
+    .await
+}
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        crates_io_api_base_url: format!("{}/api/v1", server.url()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_get_latest_version_from_crates_io() {
+    let server = start_crates_io().await;
+    let release_repo = repo_for(&server);
+
+    let version = release_repo
+        .get_latest_version(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+    assert_eq!(
+        server.requests()[0].header("user-agent"),
+        Some(ant_releases::http::DEFAULT_USER_AGENT)
+    );
+    assert!(ant_releases::http::DEFAULT_USER_AGENT
+        .ends_with(" (+https://github.com/maidsafe/ant-releases)"));
+}
+
+#[tokio::test]
+async fn should_list_all_versions_across_pages() {
+    let server = start_crates_io().await;
+    let release_repo = repo_for(&server);
+
+    let releases = release_repo
+        .list_versions(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+
+    let versions: Vec<String> = releases.iter().map(|r| r.version.to_string()).collect();
+    assert_eq!(
+        versions,
+        [
+            "0.113.0-rc.1",
+            "0.112.7",
+            "0.112.7-rc.1",
+            "0.112.6",
+            "0.112.5"
+        ]
+    );
+    assert_eq!(
+        releases[1].published_at,
+        Utc.with_ymd_and_hms(2024, 12, 12, 12, 0, 0).unwrap()
+    );
+    assert!(!releases[1].yanked);
+    assert!(releases[4].yanked);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn should_fail_to_list_versions_of_unpublished_release_type() {
+    let server = start_crates_io().await;
+    let release_repo = AntReleaseRepository {
+        version_sources: HashMap::new(),
+        ..repo_for(&server)
+    };
+
+    let result = release_repo
+        .list_versions(&ReleaseType::NodeLaunchpad)
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(Error::ReleaseNotPublishedToCratesIo(_))
+    ));
+    assert!(server.requests().is_empty());
+}
+
+#[tokio::test]
+async fn should_fail_to_list_versions_when_crate_does_not_exist() {
+    let server = start_crates_io().await;
+    let release_repo = repo_for(&server);
+
+    let result = release_repo.list_versions(&ReleaseType::AntCtl).await;
+
+    assert!(matches!(result, Err(Error::CratesIoResponseError(404))));
+}
+
+#[tokio::test]
+async fn should_fail_to_list_versions_with_invalid_date() {
+    let server = MockServer::start(|_| {
+        Response::new(
+            200,
+            r#"{ "versions": [ { "num": "0.1.0", "created_at": "yesterday", "yanked": false } ],
+                 "meta": { "next_page": null } }"#,
+        )
+    })
+    .await;
+    let release_repo = repo_for(&server);
+
+    let result = release_repo.list_versions(&ReleaseType::AntNode).await;
+
+    assert!(matches!(result, Err(Error::DateTimeParseError(_))));
+}
+
+async fn resolve(requirement: &str, include_prerelease: bool) -> Result<Version, Error> {
+    let server = start_crates_io().await;
+    repo_for(&server)
+        .resolve_version(
+            &ReleaseType::AntNode,
+            &VersionReq::parse(requirement).unwrap(),
+            include_prerelease,
+        )
+        .await
+}
+
+#[tokio::test]
+async fn should_resolve_caret_requirement_to_highest_stable_version() {
+    let version = resolve("^0.112", false).await.unwrap();
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+}
+
+#[tokio::test]
+async fn should_resolve_tilde_requirement() {
+    let version = resolve("~0.112.5", false).await.unwrap();
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+}
+
+#[tokio::test]
+async fn should_resolve_to_prerelease_only_when_included() {
+    let version = resolve(">=0.112", false).await.unwrap();
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+
+    let version = resolve(">=0.112", true).await.unwrap();
+    assert_eq!(version, Version::parse("0.113.0-rc.1").unwrap());
+}
+
+#[tokio::test]
+async fn should_resolve_requirement_naming_a_prerelease() {
+    let version = resolve("=0.112.7-rc.1", false).await.unwrap();
+    assert_eq!(version, Version::parse("0.112.7-rc.1").unwrap());
+}
+
+#[tokio::test]
+async fn should_not_resolve_to_yanked_version() {
+    let result = resolve("=0.112.5", false).await;
+
+    match result {
+        Err(Error::NoMatchingVersion {
+            release_type,
+            version_req,
+            candidates,
+        }) => {
+            assert_eq!(release_type, "antnode");
+            assert_eq!(version_req, "=0.112.5");
+            assert_eq!(candidates.len(), 4);
+            assert!(!candidates.contains(&Version::parse("0.112.5").unwrap()));
+        }
+        other => panic!("Expected NoMatchingVersion error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn should_list_candidates_when_no_version_matches() {
+    let err = resolve("^0.113", false).await.unwrap_err();
+
+    assert_eq!(
+        err.to_string(),
+        "No version of antnode matches ^0.113. Available versions: \
+         0.113.0-rc.1, 0.112.7, 0.112.7-rc.1, 0.112.6"
+    );
+}
+
+/// Starts a stand-in for the crates.io API whose `ant-node` crate has the given versions, each
+/// given as a (version, yanked) pair.
+async fn start_crates_io_with_versions(versions: &[(&str, bool)]) -> MockServer {
+    let versions = versions
+        .iter()
+        .map(|(num, yanked)| {
+            format!(
+                r#"{{ "num": "{num}", "created_at": "2024-12-01T00:00:00+00:00", "yanked": {yanked} }}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    let body = format!(r#"{{ "versions": [{versions}], "meta": {{ "next_page": null }} }}"#);
+    MockServer::start(move |_| Response::new(200, body.clone())).await
+}
+
+async fn latest_for_channel(server: &MockServer, channel: Channel) -> Result<Version, Error> {
+    repo_for(server)
+        .get_latest_version_for_channel(&ReleaseType::AntNode, &channel)
+        .await
+}
+
+#[tokio::test]
+async fn should_get_latest_version_for_each_channel() {
+    let server = start_crates_io_with_versions(&[
+        ("0.112.6", false),
+        ("0.112.7-rc.1", false),
+        ("0.112.7-rc.2", false),
+        ("0.113.0-alpha.1", false),
+        ("0.113.0-dev.3", false),
+    ])
+    .await;
+
+    let expected = [
+        (Channel::Stable, "0.112.6"),
+        (Channel::ReleaseCandidate, "0.112.7-rc.2"),
+        (Channel::Alpha, "0.113.0-alpha.1"),
+        (Channel::Any, "0.113.0-dev.3"),
+    ];
+    for (channel, version) in expected {
+        assert_eq!(
+            latest_for_channel(&server, channel).await.unwrap(),
+            Version::parse(version).unwrap(),
+            "unexpected version for the {channel} channel"
+        );
+    }
+}
+
+#[tokio::test]
+async fn should_prefer_stable_release_over_older_release_candidate() {
+    let server =
+        start_crates_io_with_versions(&[("0.112.7-rc.2", false), ("0.112.7", false)]).await;
+
+    let version = latest_for_channel(&server, Channel::ReleaseCandidate)
+        .await
+        .unwrap();
+
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+}
+
+#[tokio::test]
+async fn should_ignore_yanked_versions_on_channel() {
+    let server = start_crates_io_with_versions(&[("0.112.6", false), ("0.112.7", true)]).await;
+
+    let version = latest_for_channel(&server, Channel::Stable).await.unwrap();
+
+    assert_eq!(version, Version::parse("0.112.6").unwrap());
+}
+
+#[tokio::test]
+async fn should_fail_when_channel_has_no_versions() {
+    let server = start_crates_io_with_versions(&[("0.1.0-rc.1", false)]).await;
+
+    let result = latest_for_channel(&server, Channel::Stable).await;
+
+    match result {
+        Err(Error::LatestReleaseNotFound(msg)) => {
+            assert_eq!(msg, "antnode on the stable channel")
+        }
+        other => panic!("Expected LatestReleaseNotFound error, got {other:?}"),
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ArchiveType, Platform,
+    ProgressEvent, ReleaseType, RetryPolicy, SignatureVerification,
+};
+use assert_fs::prelude::*;
+use common::{
+    create_tar_gz, create_zip, serve_bytes, sha256_hex, MockServer, Response, TestKey, ARCHIVE_NAME,
+};
+use predicates::prelude::*;
+use semver::Version;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const ZIP_NAME: &str = "antnode-0.112.7-x86_64-pc-windows-msvc.zip";
+
+/// Contents for the binary that don't compress well, so the archive spans many chunks.
+fn binary_contents() -> Vec<u8> {
+    let mut rng = fastrand::Rng::with_seed(7);
+    (0..256 * 1024).map(|_| rng.u8(..)).collect()
+}
+
+/// Starts a server hosting `archive` under `archive_name`, along with its checksum and signature
+/// if provided.
+async fn start_server(
+    archive_name: &str,
+    archive: Vec<u8>,
+    checksum: Option<String>,
+    signature: Option<String>,
+) -> MockServer {
+    let archive_path = format!("/{archive_name}");
+    let checksum_path = format!("/{archive_name}.sha256");
+    let signature_path = format!("/{archive_name}.sig");
+    MockServer::start(move |request| {
+        if request.path == archive_path {
+            return serve_bytes(request, &archive, true);
+        }
+        let sidecar = if request.path == checksum_path {
+            checksum.as_ref()
+        } else if request.path == signature_path {
+            signature.as_ref()
+        } else {
+            None
+        };
+        match sidecar {
+            Some(contents) => Response::new(200, contents.clone()),
+            None => Response::not_found(),
+        }
+    })
+    .await
+}
+
+async fn download_and_extract_antnode(
+    release_repo: &AntReleaseRepository,
+    platform: Platform,
+    archive_type: ArchiveType,
+    dest_dir: &assert_fs::TempDir,
+) -> Result<PathBuf, Error> {
+    let progress_callback = |_event: ProgressEvent| {};
+    release_repo
+        .download_and_extract(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &platform,
+            &archive_type,
+            dest_dir,
+            &progress_callback,
+        )
+        .await
+}
+
+fn dir_entry_names(dir: &assert_fs::TempDir) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn should_report_extracting_once_binary_is_reached_in_stream() {
+    let binary = binary_contents();
+    let archive = create_tar_gz(&[("README.md", b"Read me first"), ("antnode", &binary)]);
+    let checksum = sha256_hex(&archive);
+    let server = start_server(ARCHIVE_NAME, archive, Some(checksum), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let callback = {
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    };
+
+    release_repo
+        .download_and_extract(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            &dest_dir,
+            &callback,
+        )
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap().clone();
+    let position = |wanted: &ProgressEvent| events.iter().position(|event| event == wanted);
+    let extracting = position(&ProgressEvent::Extracting).unwrap();
+    let first_downloading = events
+        .iter()
+        .position(|event| matches!(event, ProgressEvent::Downloading { .. }))
+        .unwrap();
+    assert_eq!(events[0], ProgressEvent::Connecting);
+    assert!(first_downloading < extracting, "{events:?}");
+    assert!(
+        extracting < position(&ProgressEvent::Verifying).unwrap(),
+        "{events:?}"
+    );
+    assert_eq!(
+        events
+            .iter()
+            .filter(|event| **event == ProgressEvent::Extracting)
+            .count(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn should_extract_binary_from_tar_gz_as_it_downloads() {
+    let binary = binary_contents();
+    let archive = create_tar_gz(&[("README.md", b"Read me first"), ("antnode", &binary)]);
+    let checksum = sha256_hex(&archive);
+    let server = start_server(ARCHIVE_NAME, archive, Some(checksum), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let binary_path = download_and_extract_antnode(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(binary_path, dest_dir.child("antnode").path());
+    assert_eq!(std::fs::read(&binary_path).unwrap(), binary);
+    // Neither the archive nor the other files in it are left behind.
+    assert_eq!(dir_entry_names(&dest_dir), ["antnode"]);
+}
+
+#[tokio::test]
+async fn should_not_leave_binary_when_checksum_does_not_match() {
+    let archive = create_tar_gz(&[("antnode", b"antnode binary")]);
+    let wrong_checksum = sha256_hex(b"something else entirely");
+    let server = start_server(
+        ARCHIVE_NAME,
+        archive.clone(),
+        Some(wrong_checksum.clone()),
+        None,
+    )
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let result = download_and_extract_antnode(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    match result {
+        Err(Error::ChecksumMismatch { expected, actual }) => {
+            assert_eq!(expected, wrong_checksum);
+            assert_eq!(actual, sha256_hex(&archive));
+        }
+        other => panic!("Expected ChecksumMismatch error, got {other:?}"),
+    }
+    assert!(dir_entry_names(&dest_dir).is_empty());
+}
+
+#[tokio::test]
+async fn should_fail_when_binary_is_not_in_archive() {
+    let archive = create_tar_gz(&[("README.md", b"Read me first"), ("antctl", b"antctl")]);
+    let server = start_server(ARCHIVE_NAME, archive, None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let result = download_and_extract_antnode(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    match result {
+        Err(Error::BinaryNotFoundInArchive {
+            binary_name,
+            entries,
+        }) => {
+            assert_eq!(binary_name, "antnode");
+            assert_eq!(entries, ["README.md", "antctl"]);
+        }
+        other => panic!("Expected BinaryNotFoundInArchive error, got {other:?}"),
+    }
+    assert!(dir_entry_names(&dest_dir).is_empty());
+}
+
+#[tokio::test]
+async fn should_restart_interrupted_download_from_the_beginning() {
+    let binary = binary_contents();
+    let archive = create_tar_gz(&[("antnode", &binary)]);
+    let archive_len = archive.len();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let attempts = attempts.clone();
+        move |request| {
+            if request.path != format!("/{ARCHIVE_NAME}") {
+                return Response::not_found();
+            }
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                Response::new(200, archive.clone()).truncated(archive_len / 2)
+            } else {
+                serve_bytes(request, &archive, true)
+            }
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        retry_policy: RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    let binary_path = download_and_extract_antnode(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(&binary_path).unwrap(), binary);
+    let archive_requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(archive_requests.len(), 2);
+    assert_eq!(archive_requests[1].header("range"), None);
+}
+
+#[tokio::test]
+async fn should_extract_binary_from_zip_via_temporary_archive() {
+    let archive = create_zip(&[("LICENSE", b"GPL-3.0"), ("antnode.exe", b"antnode binary")]);
+    let checksum = sha256_hex(&archive);
+    let server = start_server(ZIP_NAME, archive, Some(checksum), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let binary_path = download_and_extract_antnode(
+        &release_repo,
+        Platform::Windows,
+        ArchiveType::Zip,
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(binary_path, dest_dir.child("antnode.exe").path());
+    dest_dir.child("antnode.exe").assert("antnode binary");
+    dest_dir.child(ZIP_NAME).assert(predicate::path::missing());
+    assert_eq!(dir_entry_names(&dest_dir), ["antnode.exe"]);
+}
+
+#[tokio::test]
+async fn should_fail_before_downloading_when_required_signature_is_missing() {
+    let archive = create_tar_gz(&[("antnode", b"antnode binary")]);
+    let server = start_server(ARCHIVE_NAME, archive, None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        signature_verification: SignatureVerification {
+            trusted_public_keys: vec![
+                "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string()
+            ],
+            optional_for: HashSet::new(),
+        },
+        ..Default::default()
+    };
+
+    let result = download_and_extract_antnode(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureMissing(_))));
+    assert!(server.requests_for(&format!("/{ARCHIVE_NAME}")).is_empty());
+    assert!(dir_entry_names(&dest_dir).is_empty());
+}
+
+#[tokio::test]
+async fn should_reject_signature_for_a_different_archive_when_extracting_as_it_downloads() {
+    let key = TestKey::new(1);
+    let archive = create_tar_gz(&[("antnode", b"antnode binary")]);
+    let release_repo_for = |server: &MockServer| AntReleaseRepository {
+        antnode_base_url: server.url(),
+        signature_verification: SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        ..Default::default()
+    };
+
+    let signature = key.sign(ARCHIVE_NAME, &archive);
+    let server = start_server(ARCHIVE_NAME, archive.clone(), None, Some(signature)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    download_and_extract_antnode(
+        &release_repo_for(&server),
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+    dest_dir.child("antnode").assert("antnode binary");
+
+    let signature = key.sign("antnode-0.112.6-x86_64-unknown-linux-musl.tar.gz", &archive);
+    let server = start_server(ARCHIVE_NAME, archive, None, Some(signature)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let result = download_and_extract_antnode(
+        &release_repo_for(&server),
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureFileMismatch(_))));
+    assert!(dir_entry_names(&dest_dir).is_empty());
+}
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use ant_releases::{error::Error, AntReleaseRepoActions};
+use ant_releases::{error::Error, AntReleaseRepoActions, ByteProgress};
 use assert_fs::prelude::*;
 
 #[tokio::test]
@@ -16,7 +16,7 @@ async fn should_download_winsw() {
     download_dir.create_dir_all().unwrap();
     let downloaded_archive = download_dir.child("WinSW-x64.exe");
 
-    let progress_callback = |_downloaded: u64, _total: u64| {};
+    let progress_callback = ByteProgress(|_downloaded: u64, _total: u64| {});
     let release_repo = <dyn AntReleaseRepoActions>::default_config();
     release_repo
         .download_winsw(&downloaded_archive, &progress_callback)
@@ -35,7 +35,7 @@ async fn should_download_a_custom_binary() {
         download_dir.child("safenode-charlie-x86_64-unknown-linux-musl.tar.gz");
 
     let url = "https://sn-node.s3.eu-west-2.amazonaws.com/jacderida/file-upload-address/safenode-charlie-x86_64-unknown-linux-musl.tar.gz";
-    let progress_callback = |_downloaded: u64, _total: u64| {};
+    let progress_callback = ByteProgress(|_downloaded: u64, _total: u64| {});
     let release_repo = <dyn AntReleaseRepoActions>::default_config();
     release_repo
         .download_release(url, &download_dir, &progress_callback)
@@ -52,7 +52,7 @@ async fn should_fail_to_download_non_archive() {
     download_dir.create_dir_all().unwrap();
 
     let url = "https://sn-node.s3.eu-west-2.amazonaws.com/jacderida/file-upload-address/safenode-charlie-x86_64-unknown-linux-musl.txt";
-    let progress_callback = |_downloaded: u64, _total: u64| {};
+    let progress_callback = ByteProgress(|_downloaded: u64, _total: u64| {});
     let release_repo = <dyn AntReleaseRepoActions>::default_config();
     let result = release_repo
         .download_release(url, &download_dir, &progress_callback)
@@ -64,7 +64,7 @@ async fn should_fail_to_download_non_archive() {
             Error::UrlIsNotArchive => {
                 assert_eq!(
                     e.to_string(),
-                    "The URL must point to a zip or gzipped tar archive"
+                    "The URL must point to a zip archive or a compressed tarball"
                 );
             }
             _ => panic!("The error type should be ReleaseBinaryNotFound"),
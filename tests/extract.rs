@@ -0,0 +1,570 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{error::Error, AntReleaseRepoActions, AntReleaseRepository, BinaryPermissions};
+use assert_fs::prelude::*;
+use common::{create_tar_gz, create_zip, create_zip_with_options};
+use predicates::prelude::*;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Entries to put in an archive, as (name, contents) pairs. Names ending in `/` are directories.
+const ANTCTL_ENTRIES: &[(&str, &[u8])] = &[
+    ("README.md", b"Read me first"),
+    ("bin/", b""),
+    ("bin/antctl", b"antctl binary"),
+    ("bin/antctld", b"antctld binary"),
+];
+
+fn write_archive(dir: &assert_fs::TempDir, name: &str, archive: &[u8]) -> PathBuf {
+    let path = dir.child(name).to_path_buf();
+    std::fs::write(&path, archive).unwrap();
+    path
+}
+
+fn extract_all_and_check(archive_path: &Path, extract_dir: &assert_fs::fixture::ChildPath) {
+    let release_repo = AntReleaseRepository::default();
+
+    let manifest = release_repo
+        .extract_all_from_archive(archive_path, extract_dir)
+        .unwrap();
+
+    let names: Vec<&str> = manifest.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["README.md", "bin/", "bin/antctl", "bin/antctld"]);
+    assert_eq!(manifest.files().count(), 3);
+    for (name, contents) in ANTCTL_ENTRIES.iter().filter(|(n, _)| !n.ends_with('/')) {
+        extract_dir.child(name).assert(*contents);
+    }
+    extract_dir.child("bin").assert(predicate::path::is_dir());
+
+    let antctld = manifest.find_file("antctld").unwrap();
+    assert_eq!(antctld.path, extract_dir.child("bin/antctld").path());
+    assert_eq!(antctld.size, 14);
+}
+
+#[test]
+fn should_extract_all_entries_of_tar_gz_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.tar.gz",
+        &create_tar_gz(ANTCTL_ENTRIES),
+    );
+
+    extract_all_and_check(&archive_path, &tmp_data_dir.child("extract"));
+}
+
+#[test]
+fn should_extract_all_entries_of_zip_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = write_archive(&tmp_data_dir, "antctl.zip", &create_zip(ANTCTL_ENTRIES));
+
+    extract_all_and_check(&archive_path, &tmp_data_dir.child("extract"));
+}
+
+#[test]
+fn should_extract_named_binary_from_archive_with_several_files() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    extract_dir.create_dir_all().unwrap();
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.tar.gz",
+        &create_tar_gz(ANTCTL_ENTRIES),
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let binary_path = release_repo
+        .extract_binary_from_archive(&archive_path, "antctld", &extract_dir)
+        .unwrap();
+
+    assert_eq!(binary_path, extract_dir.child("antctld").path());
+    extract_dir.child("antctld").assert("antctld binary");
+    extract_dir
+        .child("antctl")
+        .assert(predicate::path::missing());
+    extract_dir
+        .child("README.md")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn should_extract_windows_binary_without_giving_extension() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    extract_dir.create_dir_all().unwrap();
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.zip",
+        &create_zip(&[
+            ("LICENSE", b"GPL-3.0"),
+            ("antctl.exe", b"antctl binary"),
+            ("antctld.exe", b"antctld binary"),
+        ]),
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let binary_path = release_repo
+        .extract_binary_from_archive(&archive_path, "antctl", &extract_dir)
+        .unwrap();
+    assert_eq!(binary_path, extract_dir.child("antctl.exe").path());
+    extract_dir.child("antctl.exe").assert("antctl binary");
+
+    let binary_path = release_repo
+        .extract_binary_from_archive(&archive_path, "antctld.exe", &extract_dir)
+        .unwrap();
+    assert_eq!(binary_path, extract_dir.child("antctld.exe").path());
+}
+
+#[test]
+fn should_list_entries_when_binary_is_not_in_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.tar.gz",
+        &create_tar_gz(ANTCTL_ENTRIES),
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let result =
+        release_repo.extract_binary_from_archive(&archive_path, "antnode", tmp_data_dir.path());
+
+    match result {
+        Err(err @ Error::BinaryNotFoundInArchive { .. }) => {
+            assert_eq!(
+                err.to_string(),
+                "antnode was not found in the archive. Entries: README.md, bin/antctl, bin/antctld"
+            );
+        }
+        other => panic!("Expected BinaryNotFoundInArchive error, got {other:?}"),
+    }
+}
+
+#[test]
+fn should_extract_first_entry_of_single_binary_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    extract_dir.create_dir_all().unwrap();
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antnode.tar.gz",
+        &create_tar_gz(&[("antnode", b"antnode binary")]),
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let binary_path = release_repo
+        .extract_release_archive(&archive_path, &extract_dir)
+        .unwrap();
+
+    assert_eq!(binary_path, extract_dir.child("antnode").path());
+    extract_dir.child("antnode").assert("antnode binary");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&binary_path)
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+}
+
+/// An entry of a hostile tar archive, which is written with raw headers so that names the `tar`
+/// crate would refuse to write can be used.
+enum TarEntry<'a> {
+    File(&'a str, &'a [u8]),
+    Symlink(&'a str, &'a str),
+    Hardlink(&'a str, &'a str),
+}
+
+fn create_raw_tar_gz(dir: &assert_fs::TempDir, entries: &[TarEntry]) -> PathBuf {
+    fn set_raw_field(field: &mut [u8], value: &str) {
+        field.fill(0);
+        field[..value.len()].copy_from_slice(value.as_bytes());
+    }
+
+    let path = dir.child("hostile.tar.gz").to_path_buf();
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(&path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for entry in entries {
+        let mut header = tar::Header::new_old();
+        header.set_mode(0o644);
+        let contents: &[u8] = match entry {
+            TarEntry::File(name, contents) => {
+                set_raw_field(&mut header.as_old_mut().name, name);
+                contents
+            }
+            TarEntry::Symlink(name, target) | TarEntry::Hardlink(name, target) => {
+                header.set_entry_type(if matches!(entry, TarEntry::Symlink(..)) {
+                    tar::EntryType::Symlink
+                } else {
+                    tar::EntryType::Link
+                });
+                set_raw_field(&mut header.as_old_mut().name, name);
+                set_raw_field(&mut header.as_old_mut().linkname, target);
+                b""
+            }
+        };
+        header.set_size(contents.len() as u64);
+        header.set_cksum();
+        builder.append(&header, contents).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+    path
+}
+
+/// Extracts a hostile archive and checks it's rejected without writing outside the destination.
+fn assert_rejected(tmp_data_dir: &assert_fs::TempDir, archive_path: &Path, unsafe_entry: &str) {
+    let extract_dir = tmp_data_dir.child("a/b/extract");
+    let release_repo = AntReleaseRepository::default();
+
+    let result = release_repo.extract_all_from_archive(archive_path, &extract_dir);
+
+    match result {
+        Err(Error::UnsafeArchiveEntry(entry)) => assert_eq!(entry, unsafe_entry),
+        other => panic!("Expected UnsafeArchiveEntry error for {unsafe_entry}, got {other:?}"),
+    }
+    tmp_data_dir
+        .child("a/b/evil")
+        .assert(predicate::path::missing());
+    tmp_data_dir
+        .child("a/evil")
+        .assert(predicate::path::missing());
+    tmp_data_dir
+        .child("evil")
+        .assert(predicate::path::missing());
+}
+
+#[test]
+fn should_reject_tar_entries_with_parent_dir_components() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[
+            TarEntry::File("antnode", b"antnode binary"),
+            TarEntry::File("bin/../../evil", b"pwned"),
+        ],
+    );
+
+    assert_rejected(&tmp_data_dir, &archive_path, "bin/../../evil");
+}
+
+#[test]
+fn should_reject_tar_entries_with_absolute_paths() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let evil_path = tmp_data_dir.child("evil");
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[TarEntry::File(evil_path.to_str().unwrap(), b"pwned")],
+    );
+
+    assert_rejected(&tmp_data_dir, &archive_path, evil_path.to_str().unwrap());
+}
+
+#[test]
+fn should_reject_symlinks_escaping_destination() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[
+            TarEntry::Symlink("cron.d", "../../../etc/cron.d"),
+            TarEntry::File("cron.d/evil", b"* * * * * pwned"),
+        ],
+    );
+    assert_rejected(&tmp_data_dir, &archive_path, "cron.d");
+
+    let archive_path =
+        create_raw_tar_gz(&tmp_data_dir, &[TarEntry::Symlink("passwd", "/etc/passwd")]);
+    assert_rejected(&tmp_data_dir, &archive_path, "passwd");
+
+    let archive_path =
+        create_raw_tar_gz(&tmp_data_dir, &[TarEntry::Symlink("bin", "sub/../../evil")]);
+    assert_rejected(&tmp_data_dir, &archive_path, "bin");
+}
+
+#[cfg(unix)]
+#[test]
+fn should_reject_symlink_escaping_through_earlier_symlink() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    // The first link points at the destination itself, so a `..` in a link beneath it would
+    // lead out of the destination even though it looks like it stays inside.
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[
+            TarEntry::Symlink("self", "."),
+            TarEntry::Symlink("self/escape", "../evil"),
+        ],
+    );
+
+    assert_rejected(&tmp_data_dir, &archive_path, "self/escape");
+}
+
+#[test]
+fn should_reject_hardlinks_escaping_destination() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    tmp_data_dir.child("a/secret").write_str("secret").unwrap();
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[TarEntry::Hardlink("secret", "../../secret")],
+    );
+
+    assert_rejected(&tmp_data_dir, &archive_path, "secret");
+}
+
+#[cfg(unix)]
+#[test]
+fn should_extract_links_within_destination() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[
+            TarEntry::File("bin/antctl", b"antctl binary"),
+            TarEntry::Symlink("antctl", "bin/antctl"),
+            TarEntry::Symlink("bin/ctl", "../bin/antctl"),
+            TarEntry::Hardlink("antctl-copy", "bin/antctl"),
+        ],
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let manifest = release_repo
+        .extract_all_from_archive(&archive_path, &extract_dir)
+        .unwrap();
+
+    assert_eq!(manifest.entries.len(), 4);
+    extract_dir.child("antctl").assert("antctl binary");
+    extract_dir.child("bin/ctl").assert("antctl binary");
+    extract_dir.child("antctl-copy").assert("antctl binary");
+    assert!(
+        std::fs::symlink_metadata(extract_dir.child("antctl").path())
+            .unwrap()
+            .file_type()
+            .is_symlink()
+    );
+}
+
+#[test]
+fn should_reject_zip_entries_escaping_destination() {
+    let names = ["../../evil", "..\\..\\evil", "/tmp/evil", "C:\\evil"];
+    for name in names {
+        let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+        let archive_path = write_archive(
+            &tmp_data_dir,
+            "hostile.zip",
+            &create_zip(&[(name, b"pwned")]),
+        );
+
+        assert_rejected(&tmp_data_dir, &archive_path, name);
+    }
+}
+
+#[test]
+fn should_reject_zip_symlink_escaping_destination() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = tmp_data_dir.child("hostile.zip").to_path_buf();
+    let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive_path).unwrap());
+    writer
+        .add_symlink("etc", "../../../etc", zip::write::FileOptions::default())
+        .unwrap();
+    writer.finish().unwrap();
+
+    assert_rejected(&tmp_data_dir, &archive_path, "etc");
+}
+
+#[test]
+fn should_reject_unsafe_entry_when_extracting_named_binary() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = create_raw_tar_gz(
+        &tmp_data_dir,
+        &[
+            TarEntry::File("../antnode", b"pwned"),
+            TarEntry::File("antnode", b"antnode binary"),
+        ],
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let result =
+        release_repo.extract_binary_from_archive(&archive_path, "antnode", tmp_data_dir.path());
+
+    assert!(matches!(result, Err(Error::UnsafeArchiveEntry(entry)) if entry == "../antnode"));
+}
+
+#[cfg(unix)]
+fn get_mode(path: &Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[cfg(unix)]
+#[test]
+fn should_apply_unix_modes_from_zip_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.zip",
+        &create_zip_with_options(
+            &[("antctl", b"antctl binary")],
+            zip::write::FileOptions::default().unix_permissions(0o750),
+        ),
+    );
+    let release_repo = AntReleaseRepository::default();
+
+    let binary_path = release_repo
+        .extract_binary_from_archive(&archive_path, "antctl", &extract_dir)
+        .unwrap();
+
+    assert_eq!(get_mode(&binary_path), 0o750);
+}
+
+#[cfg(unix)]
+#[test]
+fn should_force_executable_permissions_when_configured() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    let archive_path = write_archive(
+        &tmp_data_dir,
+        "antctl.zip",
+        &create_zip_with_options(
+            &[("antctl", b"antctl binary")],
+            zip::write::FileOptions::default().unix_permissions(0o644),
+        ),
+    );
+
+    let release_repo = AntReleaseRepository::default();
+    let binary_path = release_repo
+        .extract_release_archive(&archive_path, &extract_dir)
+        .unwrap();
+    assert_eq!(get_mode(&binary_path), 0o644);
+
+    let release_repo = AntReleaseRepository {
+        binary_permissions: BinaryPermissions::Executable,
+        ..Default::default()
+    };
+    let binary_path = release_repo
+        .extract_release_archive(&archive_path, &extract_dir)
+        .unwrap();
+    assert_eq!(get_mode(&binary_path), 0o755);
+    let binary_path = release_repo
+        .extract_binary_from_archive(&archive_path, "antctl", &extract_dir)
+        .unwrap();
+    assert_eq!(get_mode(&binary_path), 0o755);
+}
+
+#[test]
+fn should_preserve_modification_times() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let extract_dir = tmp_data_dir.child("extract");
+    let release_repo = AntReleaseRepository::default();
+
+    let tar_path = tmp_data_dir.child("antnode.tar.gz").to_path_buf();
+    let encoder = flate2::write::GzEncoder::new(
+        std::fs::File::create(&tar_path).unwrap(),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    let mut header = tar::Header::new_gnu();
+    header.set_mode(0o755);
+    header.set_mtime(1_733_050_800);
+    header.set_size(14);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, "antnode", &b"antnode binary"[..])
+        .unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+
+    let binary_path = release_repo
+        .extract_release_archive(&tar_path, &extract_dir)
+        .unwrap();
+    let mtime = std::fs::metadata(&binary_path).unwrap().modified().unwrap();
+    assert_eq!(mtime, UNIX_EPOCH + Duration::from_secs(1_733_050_800));
+
+    // Zip archives record the time without a time zone, to a resolution of two seconds.
+    let zip_path = write_archive(
+        &tmp_data_dir,
+        "antctl.zip",
+        &create_zip_with_options(
+            &[("antctl", b"antctl binary")],
+            zip::write::FileOptions::default().last_modified_time(
+                zip::DateTime::from_date_and_time(2024, 12, 1, 11, 0, 0).unwrap(),
+            ),
+        ),
+    );
+    let manifest = release_repo
+        .extract_all_from_archive(&zip_path, &extract_dir)
+        .unwrap();
+    let mtime = std::fs::metadata(&manifest.entries[0].path)
+        .unwrap()
+        .modified()
+        .unwrap();
+    assert_eq!(mtime, UNIX_EPOCH + Duration::from_secs(1_733_050_800));
+}
+
+#[cfg(feature = "tar-xz")]
+#[test]
+fn should_extract_all_entries_of_tar_xz_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = tmp_data_dir.child("antctl.tar.xz").to_path_buf();
+    let encoder = xz2::write::XzEncoder::new(std::fs::File::create(&archive_path).unwrap(), 6);
+    common::write_tar(encoder, ANTCTL_ENTRIES).finish().unwrap();
+
+    extract_all_and_check(&archive_path, &tmp_data_dir.child("extract"));
+}
+
+#[cfg(feature = "tar-zst")]
+#[test]
+fn should_extract_all_entries_of_tar_zst_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = tmp_data_dir.child("antctl.tar.zst").to_path_buf();
+    let encoder =
+        zstd::stream::write::Encoder::new(std::fs::File::create(&archive_path).unwrap(), 0)
+            .unwrap();
+    common::write_tar(encoder, ANTCTL_ENTRIES).finish().unwrap();
+
+    extract_all_and_check(&archive_path, &tmp_data_dir.child("extract"));
+}
+
+#[cfg(feature = "tar-bz2")]
+#[test]
+fn should_extract_all_entries_of_tar_bz2_archive() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = tmp_data_dir.child("antctl.tar.bz2").to_path_buf();
+    let encoder = bzip2::write::BzEncoder::new(
+        std::fs::File::create(&archive_path).unwrap(),
+        bzip2::Compression::default(),
+    );
+    common::write_tar(encoder, ANTCTL_ENTRIES).finish().unwrap();
+
+    extract_all_and_check(&archive_path, &tmp_data_dir.child("extract"));
+}
+
+#[cfg(not(feature = "tar-zst"))]
+#[test]
+fn should_fail_to_extract_archive_type_that_is_not_enabled() {
+    let tmp_data_dir = assert_fs::TempDir::new().unwrap();
+    let archive_path = tmp_data_dir.child("antctl.tar.zst");
+    archive_path.write_binary(b"not really zstd").unwrap();
+    let release_repo = AntReleaseRepository::default();
+
+    let result = release_repo.extract_all_from_archive(&archive_path, &tmp_data_dir.child("out"));
+
+    match result {
+        Err(err @ Error::ArchiveTypeNotEnabled(ant_releases::ArchiveType::TarZst)) => {
+            assert_eq!(
+                err.to_string(),
+                "Extracting tar.zst archives requires the `tar-zst` feature"
+            );
+        }
+        other => panic!("Expected ArchiveTypeNotEnabled error, got {other:?}"),
+    }
+}
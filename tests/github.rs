@@ -0,0 +1,435 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, github::parse_tag_name, AntReleaseRepoActions, AntReleaseRepository, ArchiveType,
+    ChecksumVerification, Platform, ProgressEvent, ReleaseType, RetryPolicy, SignatureVerification,
+    VersionSource,
+};
+use assert_fs::prelude::*;
+use chrono::{TimeZone, Utc};
+use common::{serve_bytes, sha256_hex, MockServer, Request, Response, TestKey, ARCHIVE_NAME};
+use semver::Version;
+use std::collections::{HashMap, HashSet};
+
+const RELEASES_PATH: &str = "/repos/maidsafe/autonomi/releases";
+
+fn archive_bytes() -> Vec<u8> {
+    (0..16 * 1024).map(|i| (i % 251) as u8).collect()
+}
+
+fn release_json(tag_name: &str, draft: bool, assets: &[(&str, &str)]) -> String {
+    let assets = assets
+        .iter()
+        .map(|(name, digest)| {
+            format!(
+                r#"{{ "name": "{name}", "size": 16384, "digest": "{digest}",
+                     "browser_download_url": "http://{{host}}/download/{tag_name}/{name}" }}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"{{ "tag_name": "{tag_name}", "draft": {draft}, "prerelease": false,
+             "published_at": "2024-12-12T12:00:00Z", "assets": [{assets}] }}"#
+    )
+}
+
+/// Builds the response to a request, filling in the address of the server.
+fn respond(request: &Request, body: &str) -> Response {
+    let host = request.header("host").unwrap();
+    Response::new(200, body.replace("{host}", host))
+}
+
+/// Starts a stand-in for the GitHub API whose releases are split over two pages.
+async fn start_github() -> MockServer {
+    start_github_with_signature(None).await
+}
+
+/// Starts a stand-in for the GitHub API, as `start_github` does, which also serves the signature
+/// of the Linux archive, if given.
+async fn start_github_with_signature(signature: Option<String>) -> MockServer {
+    let digest = format!("sha256:{}", sha256_hex(&archive_bytes()));
+    let first_page = format!(
+        "[{}, {}, {}]",
+        release_json(
+            "antnode-v0.112.7",
+            false,
+            &[
+                (ARCHIVE_NAME, &digest),
+                ("antnode-0.112.7-x86_64-pc-windows-msvc.zip", ""),
+            ]
+        ),
+        release_json("antctl-v0.11.3", false, &[]),
+        release_json("stable-2024.12.1", false, &[]),
+    );
+    let second_page = format!(
+        "[{}, {}]",
+        release_json("antnode-v0.112.6", false, &[]),
+        release_json("antnode-v0.113.0", true, &[]),
+    );
+    let tag_page = release_json(
+        "antnode-v0.112.7",
+        false,
+        &[
+            (ARCHIVE_NAME, &digest),
+            ("antnode-0.112.7-x86_64-pc-windows-msvc.zip", ""),
+        ],
+    );
+    let body = archive_bytes();
+
+    MockServer::start(move |request| match request.path.as_str() {
+        path if path == format!("{RELEASES_PATH}?per_page=100") => {
+            let host = request.header("host").unwrap();
+            respond(request, &first_page).with_header(
+                "Link",
+                &format!(
+                    "<http://{host}{RELEASES_PATH}?per_page=100&page=2>; rel=\"next\", \
+                     <http://{host}{RELEASES_PATH}?per_page=100&page=2>; rel=\"last\""
+                ),
+            )
+        }
+        path if path == format!("{RELEASES_PATH}?per_page=100&page=2") => {
+            respond(request, &second_page)
+        }
+        path if path == format!("{RELEASES_PATH}/tags/antnode-v0.112.7") => {
+            respond(request, &tag_page)
+        }
+        path if path == format!("/download/antnode-v0.112.7/{ARCHIVE_NAME}") => {
+            serve_bytes(request, &body, false)
+        }
+        path if path == format!("/download/antnode-v0.112.7/{ARCHIVE_NAME}.sig") => {
+            match &signature {
+                Some(signature) => Response::new(200, signature.clone()),
+                None => Response::not_found(),
+            }
+        }
+        _ => Response::not_found(),
+    })
+    .await
+}
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        github_api_base_url: server.url(),
+        retry_policy: RetryPolicy::no_retries(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn should_parse_tag_names() {
+    assert_eq!(
+        parse_tag_name("antnode-v0.112.7").unwrap(),
+        (ReleaseType::AntNode, Version::parse("0.112.7").unwrap())
+    );
+    assert_eq!(
+        parse_tag_name("nat-detection-v0.2.11-rc.1").unwrap(),
+        (
+            ReleaseType::NatDetection,
+            Version::parse("0.2.11-rc.1").unwrap()
+        )
+    );
+    assert!(matches!(
+        parse_tag_name("stable-2024.12.1"),
+        Err(Error::TagNameVersionParsingFailed)
+    ));
+    assert!(matches!(
+        parse_tag_name("antnode-0.112.7"),
+        Err(Error::TagNameVersionParsingFailed)
+    ));
+    // Tags name the binary, so a crate name, which may be shared by several release types, isn't
+    // accepted.
+    assert!(matches!(
+        parse_tag_name("ant-node-manager-v0.11.0"),
+        Err(Error::TagNameVersionParsingFailed)
+    ));
+}
+
+#[tokio::test]
+async fn should_list_github_releases_across_pages() {
+    let server = start_github().await;
+    let release_repo = repo_for(&server);
+
+    let releases = release_repo
+        .list_github_releases(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+
+    let tags: Vec<&str> = releases.iter().map(|r| r.tag_name.as_str()).collect();
+    assert_eq!(tags, ["antnode-v0.112.7", "antnode-v0.112.6"]);
+    assert_eq!(
+        releases[0].published_at,
+        Utc.with_ymd_and_hms(2024, 12, 12, 12, 0, 0).unwrap()
+    );
+    assert_eq!(
+        releases[0].platforms(),
+        [Platform::LinuxMusl, Platform::Windows]
+    );
+    let asset = releases[0]
+        .find_asset(&Platform::Windows, &ArchiveType::Zip)
+        .unwrap();
+    assert_eq!(asset.name, "antnode-0.112.7-x86_64-pc-windows-msvc.zip");
+    assert_eq!(asset.sha256, None);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn should_authenticate_with_github_token() {
+    let server = start_github().await;
+    let release_repo = AntReleaseRepository {
+        github_token: Some("ghp_secret".to_string()),
+        ..repo_for(&server)
+    };
+
+    release_repo
+        .list_github_releases(&ReleaseType::AntCtl)
+        .await
+        .unwrap();
+
+    for request in server.requests() {
+        assert_eq!(request.header("authorization"), Some("Bearer ghp_secret"));
+        assert_eq!(
+            request.header("accept"),
+            Some("application/vnd.github+json")
+        );
+    }
+}
+
+#[tokio::test]
+async fn should_fail_with_rate_limit_error_when_limit_is_exhausted() {
+    let server = MockServer::start(|_| {
+        Response::new(403, r#"{ "message": "API rate limit exceeded" }"#)
+            .with_header("X-RateLimit-Limit", "60")
+            .with_header("X-RateLimit-Remaining", "0")
+            .with_header("X-RateLimit-Reset", "1734000000")
+    })
+    .await;
+    let release_repo = AntReleaseRepository {
+        github_api_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let result = release_repo
+        .list_github_releases(&ReleaseType::AntNode)
+        .await;
+
+    match result {
+        Err(Error::GitHubRateLimitExceeded { limit, reset_at }) => {
+            assert_eq!(limit, 60);
+            assert_eq!(reset_at, Utc.timestamp_opt(1734000000, 0).unwrap());
+        }
+        other => panic!("Expected GitHubRateLimitExceeded error, got {other:?}"),
+    }
+    // Waiting for the limit to reset could take up to an hour, so the request isn't retried.
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn should_download_release_from_github() {
+    let server = start_github().await;
+    let release_repo = repo_for(&server);
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let archive_path = release_repo
+        .download_release_from_github(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            dest_dir.path(),
+            &|_: ProgressEvent| {},
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(archive_path, dest_dir.child(ARCHIVE_NAME).path());
+    assert_eq!(std::fs::read(&archive_path).unwrap(), archive_bytes());
+}
+
+async fn download_antnode_from_github(
+    release_repo: &AntReleaseRepository,
+    platform: Platform,
+    archive_type: ArchiveType,
+    dest_dir: &assert_fs::TempDir,
+) -> Result<std::path::PathBuf, Error> {
+    release_repo
+        .download_release_from_github(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &platform,
+            &archive_type,
+            dest_dir.path(),
+            &|_: ProgressEvent| {},
+        )
+        .await
+}
+
+#[tokio::test]
+async fn should_verify_signature_of_release_from_github() {
+    let key = TestKey::new(1);
+    let server = start_github_with_signature(Some(key.sign(ARCHIVE_NAME, &archive_bytes()))).await;
+    let release_repo = AntReleaseRepository {
+        signature_verification: SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        ..repo_for(&server)
+    };
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let archive_path = download_antnode_from_github(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(&archive_path).unwrap(), archive_bytes());
+}
+
+#[tokio::test]
+async fn should_fail_when_required_signature_is_missing_from_github_release() {
+    let server = start_github().await;
+    let release_repo = AntReleaseRepository {
+        signature_verification: SignatureVerification {
+            trusted_public_keys: vec![TestKey::new(1).public_key()],
+            optional_for: HashSet::new(),
+        },
+        ..repo_for(&server)
+    };
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_antnode_from_github(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureMissing(_))));
+    assert!(server
+        .requests_for(&format!("/download/antnode-v0.112.7/{ARCHIVE_NAME}"))
+        .is_empty());
+}
+
+#[tokio::test]
+async fn should_reject_release_from_github_with_invalid_signature() {
+    let key = TestKey::new(1);
+    let server =
+        start_github_with_signature(Some(key.sign(ARCHIVE_NAME, b"a different archive"))).await;
+    let release_repo = AntReleaseRepository {
+        signature_verification: SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        ..repo_for(&server)
+    };
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_antnode_from_github(
+        &release_repo,
+        Platform::LinuxMusl,
+        ArchiveType::TarGz,
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureInvalid(_))));
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn should_fail_when_required_checksum_is_missing_from_github_release() {
+    let server = start_github().await;
+    let release_repo = AntReleaseRepository {
+        checksum_verification: ChecksumVerification::Required,
+        ..repo_for(&server)
+    };
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    // GitHub reports no digest for the Windows archive, and no checksum is attached for it.
+    let result = download_antnode_from_github(
+        &release_repo,
+        Platform::Windows,
+        ArchiveType::Zip,
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::ChecksumMissing(_))));
+}
+
+#[tokio::test]
+async fn should_fail_to_download_release_without_asset_for_platform() {
+    let server = start_github().await;
+    let release_repo = repo_for(&server);
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = release_repo
+        .download_release_from_github(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.7").unwrap(),
+            &Platform::MacOsAarch64,
+            &ArchiveType::TarGz,
+            dest_dir.path(),
+            &|_: ProgressEvent| {},
+        )
+        .await;
+
+    match result {
+        Err(Error::ReleaseBinaryNotFound(name)) => {
+            assert_eq!(name, "antnode-0.112.7-aarch64-apple-darwin.tar.gz")
+        }
+        other => panic!("Expected ReleaseBinaryNotFound error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn should_fail_to_download_release_without_github_release() {
+    let server = start_github().await;
+    let release_repo = repo_for(&server);
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = release_repo
+        .download_release_from_github(
+            &ReleaseType::AntNode,
+            &Version::parse("0.112.5").unwrap(),
+            &Platform::LinuxMusl,
+            &ArchiveType::TarGz,
+            dest_dir.path(),
+            &|_: ProgressEvent| {},
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::ReleaseBinaryNotFound(tag)) if tag == "antnode-v0.112.5"));
+}
+
+#[tokio::test]
+async fn should_get_latest_version_from_github_releases() {
+    let server = start_github().await;
+    let release_repo = AntReleaseRepository {
+        version_sources: HashMap::from([(ReleaseType::AntNode, VersionSource::GitHubReleases)]),
+        ..repo_for(&server)
+    };
+
+    let version = release_repo
+        .get_latest_version(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+
+    // The draft release of 0.113.0 isn't considered to be published.
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+}
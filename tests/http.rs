@@ -0,0 +1,163 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, HttpConfig, ProgressEvent,
+    RetryPolicy,
+};
+use common::{serve_bytes, MockServer, Response, ARCHIVE_CONTENTS, ARCHIVE_NAME};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn repo_with(http_config: HttpConfig) -> AntReleaseRepository {
+    AntReleaseRepository {
+        http_client: http_config.build_client().unwrap(),
+        retry_policy: RetryPolicy::no_retries(),
+        ..Default::default()
+    }
+}
+
+async fn download(
+    release_repo: &AntReleaseRepository,
+    url: &str,
+    dest_dir: &assert_fs::TempDir,
+) -> Result<PathBuf, Error> {
+    release_repo
+        .download_release(url, dest_dir, &|_: ProgressEvent| {})
+        .await
+}
+
+#[tokio::test]
+async fn should_send_configured_user_agent_and_headers() {
+    let server = MockServer::start(|request| serve_bytes(request, ARCHIVE_CONTENTS, true)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with(HttpConfig {
+        user_agent: "provisioner/1.0".to_string(),
+        headers: HashMap::from([("X-Mirror-Token".to_string(), "secret".to_string())]),
+        ..Default::default()
+    });
+
+    download(
+        &release_repo,
+        &format!("{}/{ARCHIVE_NAME}", server.url()),
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("user-agent"), Some("provisioner/1.0"));
+    assert_eq!(request.header("x-mirror-token"), Some("secret"));
+}
+
+#[tokio::test]
+async fn should_send_requests_through_configured_proxy() {
+    let proxy = MockServer::start(|request| serve_bytes(request, ARCHIVE_CONTENTS, true)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with(HttpConfig {
+        proxy: Some(proxy.url()),
+        no_proxy: Some(String::new()),
+        ..Default::default()
+    });
+    // The host doesn't exist, so the download only succeeds if it goes through the proxy.
+    let url = format!("http://antnode.invalid/{ARCHIVE_NAME}");
+
+    download(&release_repo, &url, &dest_dir).await.unwrap();
+
+    assert_eq!(proxy.requests()[0].path, url);
+}
+
+#[tokio::test]
+async fn should_bypass_proxy_for_no_proxy_hosts() {
+    let server = MockServer::start(|request| serve_bytes(request, ARCHIVE_CONTENTS, true)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with(HttpConfig {
+        // Nothing listens on the discard port, so requests sent to the proxy would fail.
+        proxy: Some("http://127.0.0.1:9".to_string()),
+        no_proxy: Some("127.0.0.1".to_string()),
+        ..Default::default()
+    });
+
+    download(
+        &release_repo,
+        &format!("{}/{ARCHIVE_NAME}", server.url()),
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn should_stop_following_redirects_at_limit() {
+    let server = MockServer::start(|_| {
+        Response::new(302, "").with_header("Location", &format!("/{ARCHIVE_NAME}"))
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with(HttpConfig {
+        max_redirects: 2,
+        ..Default::default()
+    });
+
+    let result = download(
+        &release_repo,
+        &format!("{}/{ARCHIVE_NAME}", server.url()),
+        &dest_dir,
+    )
+    .await;
+
+    match result {
+        Err(Error::ReqwestError(err)) => assert!(err.is_redirect()),
+        other => panic!("Expected a redirect error, got {other:?}"),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn should_time_out_when_server_stalls() {
+    let server = MockServer::start(|request| {
+        std::thread::sleep(Duration::from_millis(500));
+        serve_bytes(request, ARCHIVE_CONTENTS, true)
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with(HttpConfig {
+        read_timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+
+    let result = download(
+        &release_repo,
+        &format!("{}/{ARCHIVE_NAME}", server.url()),
+        &dest_dir,
+    )
+    .await;
+
+    match result {
+        Err(Error::ReqwestError(err)) => assert!(err.is_timeout(), "unexpected error: {err}"),
+        other => panic!("Expected a timeout error, got {other:?}"),
+    }
+}
+
+#[test]
+fn should_reject_invalid_header() {
+    let http_config = HttpConfig {
+        headers: HashMap::from([("X-Token".to_string(), "line\nbreak".to_string())]),
+        ..Default::default()
+    };
+
+    match http_config.build_client() {
+        Err(Error::InvalidHttpHeader(name)) => assert_eq!(name, "X-Token"),
+        other => panic!("Expected InvalidHttpHeader error, got {other:?}"),
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{error::Error, AntReleaseRepository, ReleaseType, RetryPolicy};
+use common::{
+    download_antnode, serve_bytes, start_archive_server, MockServer, Response, ARCHIVE_CONTENTS,
+    ARCHIVE_NAME,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Starts a server that responds to every request with the status.
+async fn start_failing_server(status: u16) -> MockServer {
+    MockServer::start(move |_| Response::new(status, "")).await
+}
+
+/// Returns the URL of a port nothing is listening on, so connections to it are refused.
+fn unreachable_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+fn repo_with_mirrors(base_url: String, mirrors: Vec<String>) -> AntReleaseRepository {
+    AntReleaseRepository {
+        antnode_base_url: base_url,
+        mirrors: HashMap::from([(ReleaseType::AntNode, mirrors)]),
+        retry_policy: RetryPolicy::no_retries(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_fall_over_to_mirror_when_base_url_responds_with_server_error() {
+    let primary = start_failing_server(503).await;
+    let mirror = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(primary.url(), vec![mirror.url()]);
+
+    let archive_path = download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+    assert!(release_repo
+        .mirror_health
+        .is_cooling_down(&primary.url(), release_repo.mirror_cool_down));
+    assert!(!release_repo
+        .mirror_health
+        .is_cooling_down(&mirror.url(), release_repo.mirror_cool_down));
+}
+
+#[tokio::test]
+async fn should_fall_over_to_mirror_when_base_url_is_unreachable() {
+    let mirror = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(unreachable_url(), vec![mirror.url()]);
+
+    let archive_path = download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+}
+
+#[tokio::test]
+async fn should_fall_over_to_mirror_when_connection_drops_partway_through_archive() {
+    let primary = MockServer::start(|request| {
+        if request.path == format!("/{ARCHIVE_NAME}") {
+            serve_bytes(request, ARCHIVE_CONTENTS, true).truncated(ARCHIVE_CONTENTS.len() / 2)
+        } else {
+            Response::not_found()
+        }
+    })
+    .await;
+    let mirror = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(primary.url(), vec![mirror.url()]);
+
+    let archive_path = download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+    assert!(release_repo
+        .mirror_health
+        .is_cooling_down(&primary.url(), release_repo.mirror_cool_down));
+    // The mirror serves the same archive, so the download carries on where the primary stopped.
+    let mirror_requests = mirror.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(
+        mirror_requests[0].header("range"),
+        Some(format!("bytes={}-", ARCHIVE_CONTENTS.len() / 2).as_str())
+    );
+}
+
+#[tokio::test]
+async fn should_try_failed_mirror_last_until_cool_down_passes() {
+    let primary = start_failing_server(500).await;
+    let mirror = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(primary.url(), vec![mirror.url()]);
+
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+    let primary_requests = primary.requests().len();
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert!(primary_requests > 0);
+    assert_eq!(primary.requests().len(), primary_requests);
+
+    // Once the cool-down has passed, the base URL is tried first again.
+    let release_repo = AntReleaseRepository {
+        mirror_cool_down: Duration::ZERO,
+        ..release_repo
+    };
+    download_antnode(&release_repo, &dest_dir).await.unwrap();
+
+    assert!(primary.requests().len() > primary_requests);
+}
+
+#[tokio::test]
+async fn should_return_last_error_when_every_mirror_fails() {
+    let primary = start_failing_server(503).await;
+    let mirror = start_failing_server(502).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(primary.url(), vec![mirror.url()]);
+
+    let result = download_antnode(&release_repo, &dest_dir).await;
+
+    match result {
+        Err(Error::UnexpectedResponseStatus(status, url)) => {
+            assert_eq!(status, 502);
+            assert!(url.starts_with(&mirror.url()), "{url}");
+        }
+        other => panic!("Expected UnexpectedResponseStatus error, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn should_verify_checksum_of_archive_from_mirror() {
+    let mirror = MockServer::start(|request| {
+        if request.path == format!("/{ARCHIVE_NAME}") {
+            serve_bytes(request, ARCHIVE_CONTENTS, true)
+        } else if request.path == format!("/{ARCHIVE_NAME}.sha256") {
+            Response::new(200, "0".repeat(64))
+        } else {
+            Response::not_found()
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(unreachable_url(), vec![mirror.url()]);
+
+    let result = download_antnode(&release_repo, &dest_dir).await;
+
+    assert!(matches!(result, Err(Error::ChecksumMismatch { .. })));
+    assert!(!dest_dir.path().join(ARCHIVE_NAME).exists());
+}
+
+#[tokio::test]
+async fn should_not_fall_over_when_release_is_missing() {
+    let primary = MockServer::start(|_| Response::not_found()).await;
+    let mirror = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = repo_with_mirrors(primary.url(), vec![mirror.url()]);
+
+    let result = download_antnode(&release_repo, &dest_dir).await;
+
+    assert!(matches!(result, Err(Error::ReleaseBinaryNotFound(_))));
+    assert!(mirror.requests().is_empty());
+    assert!(!release_repo
+        .mirror_health
+        .is_cooling_down(&primary.url(), release_repo.mirror_cool_down));
+}
@@ -0,0 +1,209 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    AntReleaseRepoActions, AntReleaseRepository, ByteProgress, ProgressEvent, RetryPolicy,
+};
+use common::{
+    download_antnode_with_progress, serve_bytes, sha256_hex, start_archive_server, MockServer,
+    Response, ARCHIVE_CONTENTS, ARCHIVE_NAME,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        antnode_base_url: server.url(),
+        retry_policy: RetryPolicy {
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_report_each_stage_of_download() {
+    let server = start_archive_server(Some(sha256_hex(ARCHIVE_CONTENTS)), None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let callback = {
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    };
+
+    let archive_path = download_antnode_with_progress(&repo_for(&server), &dest_dir, &callback)
+        .await
+        .unwrap();
+
+    let total = Some(ARCHIVE_CONTENTS.len() as u64);
+    let events = events.lock().unwrap().clone();
+    assert_eq!(events.first(), Some(&ProgressEvent::Connecting));
+    assert_eq!(
+        events[1],
+        ProgressEvent::Downloading {
+            downloaded: 0,
+            total
+        }
+    );
+    assert_eq!(
+        events[events.len() - 3..],
+        [
+            ProgressEvent::Downloading {
+                downloaded: ARCHIVE_CONTENTS.len() as u64,
+                total
+            },
+            ProgressEvent::Verifying,
+            ProgressEvent::Completed { path: archive_path }
+        ]
+    );
+}
+
+#[tokio::test]
+async fn should_report_unknown_total_size_as_none() {
+    let server = MockServer::start(|_| Response::chunked(200, ARCHIVE_CONTENTS)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let release_repo = AntReleaseRepository::default();
+    let url = format!("{}/{}", server.url(), ARCHIVE_NAME);
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let callback = {
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    };
+
+    release_repo
+        .download_release(&url, &dest_dir, &callback)
+        .await
+        .unwrap();
+
+    let events = events.lock().unwrap().clone();
+    assert!(events.contains(&ProgressEvent::Downloading {
+        downloaded: ARCHIVE_CONTENTS.len() as u64,
+        total: None
+    }));
+
+    // The original callback signature still sees a total of 0 when the size isn't known.
+    let totals = Arc::new(Mutex::new(Vec::new()));
+    let legacy_callback = ByteProgress({
+        let totals = totals.clone();
+        move |_downloaded: u64, total: u64| totals.lock().unwrap().push(total)
+    });
+    release_repo
+        .download_release(&url, &dest_dir, &legacy_callback)
+        .await
+        .unwrap();
+    let totals = totals.lock().unwrap().clone();
+    assert!(!totals.is_empty());
+    assert!(totals.iter().all(|total| *total == 0));
+}
+
+#[tokio::test]
+async fn should_report_retries_as_events() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let server = MockServer::start({
+        let requests = requests.clone();
+        move |request| {
+            if request.path != format!("/{ARCHIVE_NAME}") {
+                return Response::not_found();
+            }
+            if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                Response::new(503, "Slow Down")
+            } else {
+                serve_bytes(request, ARCHIVE_CONTENTS, true)
+            }
+        }
+    })
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let retries = Arc::new(AtomicUsize::new(0));
+    let release_repo = AntReleaseRepository {
+        on_retry: Some(Arc::new({
+            let retries = retries.clone();
+            move |_, _| {
+                retries.fetch_add(1, Ordering::SeqCst);
+            }
+        })),
+        ..repo_for(&server)
+    };
+    let events = Arc::new(Mutex::new(Vec::new()));
+
+    let callback = {
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    };
+
+    download_antnode_with_progress(&release_repo, &dest_dir, &callback)
+        .await
+        .unwrap();
+
+    let retrying: Vec<ProgressEvent> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|event| matches!(event, ProgressEvent::Retrying { .. }))
+        .cloned()
+        .collect();
+    match retrying.as_slice() {
+        [ProgressEvent::Retrying { attempt, reason }] => {
+            assert_eq!(*attempt, 2);
+            assert!(reason.contains("503"), "unexpected reason: {reason}");
+        }
+        other => panic!("Expected a single Retrying event, got {other:?}"),
+    }
+    assert_eq!(retries.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn should_send_events_on_channel() {
+    let server = MockServer::start(|_| Response::not_found()).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    let result = download_antnode_with_progress(&repo_for(&server), &dest_dir, &sender).await;
+    drop(sender);
+
+    assert!(result.is_err());
+    let mut events = Vec::new();
+    while let Some(event) = receiver.recv().await {
+        events.push(event);
+    }
+    assert_eq!(events.first(), Some(&ProgressEvent::Connecting));
+    match events.last() {
+        Some(ProgressEvent::Failed { reason }) => {
+            assert!(
+                reason.contains("was not found"),
+                "unexpected reason: {reason}"
+            )
+        }
+        other => panic!("Expected a Failed event, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn should_hold_latest_event_in_watch_channel() {
+    let server = MockServer::start(|request| serve_bytes(request, ARCHIVE_CONTENTS, true)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let (sender, receiver) = tokio::sync::watch::channel(ProgressEvent::ResolvingVersion);
+    let url = format!("{}/{}", server.url(), ARCHIVE_NAME);
+
+    let archive_path = AntReleaseRepository::default()
+        .download_release(&url, &dest_dir, &sender)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *receiver.borrow(),
+        ProgressEvent::Completed { path: archive_path }
+    );
+}
@@ -0,0 +1,124 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_releases::{error::Error, ArchiveType, Platform, ReleaseArtifact, ReleaseType};
+use semver::Version;
+
+#[test]
+fn should_parse_archive_file_name() {
+    let artifact =
+        ReleaseArtifact::parse("antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz").unwrap();
+
+    assert_eq!(artifact.release_type, ReleaseType::AntNode);
+    assert_eq!(artifact.version, Version::parse("0.112.7").unwrap());
+    assert_eq!(artifact.platform, Platform::LinuxMusl);
+    assert_eq!(artifact.archive_type, ArchiveType::TarGz);
+}
+
+#[test]
+fn should_parse_names_containing_dashes() {
+    let artifact =
+        ReleaseArtifact::parse("nat-detection-0.2.11-rc.1-aarch64-apple-darwin.zip").unwrap();
+    assert_eq!(artifact.release_type, ReleaseType::NatDetection);
+    assert_eq!(artifact.version, Version::parse("0.2.11-rc.1").unwrap());
+    assert_eq!(artifact.platform, Platform::MacOsAarch64);
+    assert_eq!(artifact.archive_type, ArchiveType::Zip);
+
+    let artifact = ReleaseArtifact::parse(
+        "node-launchpad-0.5.0-alpha.2+build.7-armv7-unknown-linux-musleabihf.tar.gz",
+    )
+    .unwrap();
+    assert_eq!(artifact.release_type, ReleaseType::NodeLaunchpad);
+    assert_eq!(
+        artifact.version,
+        Version::parse("0.5.0-alpha.2+build.7").unwrap()
+    );
+    assert_eq!(artifact.platform, Platform::LinuxMuslArmV7);
+}
+
+#[test]
+fn should_distinguish_release_types_sharing_a_prefix() {
+    let artifact = ReleaseArtifact::parse("antctld-0.11.3-x86_64-pc-windows-msvc.zip").unwrap();
+    assert_eq!(artifact.release_type, ReleaseType::AntCtlDaemon);
+
+    let artifact =
+        ReleaseArtifact::parse("antnode_rpc_client-0.6.36-arm-unknown-linux-musleabi.tar.gz")
+            .unwrap();
+    assert_eq!(artifact.release_type, ReleaseType::AntNodeRpcClient);
+    assert_eq!(artifact.platform, Platform::LinuxMuslArm);
+}
+
+#[test]
+fn should_round_trip_file_name() {
+    let names = [
+        "ant-0.3.1-x86_64-apple-darwin.tar.gz",
+        "antctl-0.11.3-aarch64-unknown-linux-musl.zip",
+        "nat-detection-0.2.11-rc.1-x86_64-pc-windows-msvc.zip",
+    ];
+    for name in names {
+        let artifact = ReleaseArtifact::parse(name).unwrap();
+        assert_eq!(artifact.to_file_name(), name);
+        assert_eq!(artifact.to_string(), name);
+    }
+}
+
+#[test]
+fn should_build_url_from_base_url() {
+    let artifact = ReleaseArtifact::new(
+        ReleaseType::AntNode,
+        Version::parse("0.112.7").unwrap(),
+        Platform::Windows,
+        ArchiveType::Zip,
+    );
+
+    let expected =
+        "https://antnode.s3.eu-west-2.amazonaws.com/antnode-0.112.7-x86_64-pc-windows-msvc.zip";
+    assert_eq!(
+        artifact.to_url("https://antnode.s3.eu-west-2.amazonaws.com"),
+        expected
+    );
+    assert_eq!(
+        artifact.to_url("https://antnode.s3.eu-west-2.amazonaws.com/"),
+        expected
+    );
+}
+
+#[test]
+fn should_fail_to_parse_invalid_names() {
+    let names = [
+        "antnode-0.112.7-x86_64-unknown-linux-musl.tar.lz",
+        "antnode-latest-x86_64-unknown-linux-musl.tar.gz",
+        "antnode-0.112.7-x86_64-unknown-freebsd.tar.gz",
+        "safenode-0.112.7-x86_64-unknown-linux-musl.tar.gz",
+        "antnode-0.112.7.tar.gz",
+        "WinSW-x64.exe",
+    ];
+    for name in names {
+        match ReleaseArtifact::parse(name) {
+            Err(Error::InvalidArchiveName(invalid)) => assert_eq!(invalid, name),
+            other => panic!("Expected InvalidArchiveName error for {name}, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn should_round_trip_every_artifact_and_reject_crate_names() {
+    let version = Version::parse("0.112.7").unwrap();
+    for release_type in ReleaseType::all() {
+        for platform in Platform::all() {
+            let artifact =
+                ReleaseArtifact::new(release_type, version.clone(), platform, ArchiveType::TarGz);
+            assert_eq!(
+                ReleaseArtifact::parse(&artifact.to_file_name()).unwrap(),
+                artifact
+            );
+        }
+    }
+
+    assert!(ReleaseArtifact::parse("ant-node-0.112.7-x86_64-unknown-linux-musl.tar.gz").is_err());
+}
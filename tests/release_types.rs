@@ -0,0 +1,187 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+use ant_releases::{error::Error, ArchiveType, Platform, ReleaseType};
+
+#[test]
+fn should_round_trip_release_types_through_display() {
+    assert_eq!(ReleaseType::all().count(), 7);
+    for release_type in ReleaseType::all() {
+        assert_eq!(
+            release_type.to_string().parse::<ReleaseType>().unwrap(),
+            release_type
+        );
+    }
+}
+
+#[test]
+fn should_parse_release_type_from_crate_name() {
+    let expected = [
+        ("ant-cli", ReleaseType::Ant),
+        ("ant-node-manager", ReleaseType::AntCtl),
+        ("ant-node", ReleaseType::AntNode),
+        ("ant-node-rpc-client", ReleaseType::AntNodeRpcClient),
+        ("nat-detection", ReleaseType::NatDetection),
+        ("node-launchpad", ReleaseType::NodeLaunchpad),
+    ];
+    for (crate_name, release_type) in expected {
+        assert_eq!(ReleaseType::from_crate_name(crate_name), Some(release_type));
+    }
+    assert_eq!(ReleaseType::from_crate_name("antnode"), None);
+}
+
+#[test]
+fn should_parse_both_binary_and_crate_names_of_release_types() {
+    let expected = [
+        ("ant", ReleaseType::Ant),
+        ("ant-cli", ReleaseType::Ant),
+        ("antctl", ReleaseType::AntCtl),
+        ("antctld", ReleaseType::AntCtlDaemon),
+        ("ant-node-manager", ReleaseType::AntCtl),
+        ("antnode", ReleaseType::AntNode),
+        ("ant-node", ReleaseType::AntNode),
+        ("antnode_rpc_client", ReleaseType::AntNodeRpcClient),
+        ("ant-node-rpc-client", ReleaseType::AntNodeRpcClient),
+    ];
+    for (name, release_type) in expected {
+        assert_eq!(name.parse::<ReleaseType>().unwrap(), release_type);
+    }
+}
+
+#[test]
+fn should_round_trip_platforms_and_archive_types_through_display() {
+    assert_eq!(Platform::all().count(), 7);
+    for platform in Platform::all() {
+        assert_eq!(platform.to_string().parse::<Platform>().unwrap(), platform);
+    }
+    assert_eq!(
+        ArchiveType::all().collect::<Vec<_>>(),
+        [
+            ArchiveType::TarGz,
+            ArchiveType::Zip,
+            ArchiveType::TarXz,
+            ArchiveType::TarZst,
+            ArchiveType::TarBz2
+        ]
+    );
+    for archive_type in ArchiveType::all() {
+        assert_eq!(
+            archive_type.to_string().parse::<ArchiveType>().unwrap(),
+            archive_type
+        );
+    }
+}
+
+#[test]
+fn should_fail_to_parse_unknown_names() {
+    assert!(matches!(
+        "safenode".parse::<ReleaseType>(),
+        Err(Error::InvalidReleaseType(name)) if name == "safenode"
+    ));
+    assert!(matches!(
+        "x86_64-unknown-freebsd".parse::<Platform>(),
+        Err(Error::InvalidPlatform(triple)) if triple == "x86_64-unknown-freebsd"
+    ));
+    assert!(matches!(
+        "tar.lz".parse::<ArchiveType>(),
+        Err(Error::InvalidArchiveType(ext)) if ext == "tar.lz"
+    ));
+}
+
+#[test]
+fn should_detect_archive_type_from_file_name() {
+    let names = [
+        (
+            "antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz",
+            Some(ArchiveType::TarGz),
+        ),
+        (
+            "antnode-0.112.7-x86_64-unknown-linux-musl.tar.zst",
+            Some(ArchiveType::TarZst),
+        ),
+        (
+            "antnode-0.112.7-x86_64-unknown-linux-musl.tar.xz",
+            Some(ArchiveType::TarXz),
+        ),
+        (
+            "antnode-0.112.7-x86_64-unknown-linux-musl.tar.bz2",
+            Some(ArchiveType::TarBz2),
+        ),
+        (
+            "antnode-0.112.7-x86_64-pc-windows-msvc.zip",
+            Some(ArchiveType::Zip),
+        ),
+        ("antnode-0.112.7-x86_64-unknown-linux-musl.gz", None),
+        ("antnode-0.112.7-x86_64-unknown-linux-musl.zst", None),
+        ("antnode.tar", None),
+        ("tar.gz", None),
+        (".tar.gz", None),
+    ];
+    for (name, archive_type) in names {
+        assert_eq!(
+            ArchiveType::from_file_name(name),
+            archive_type,
+            "unexpected archive type for {name}"
+        );
+    }
+}
+
+#[test]
+fn should_order_enums_by_declaration() {
+    let mut release_types = vec![
+        ReleaseType::NodeLaunchpad,
+        ReleaseType::Ant,
+        ReleaseType::AntNode,
+    ];
+    release_types.sort();
+    assert_eq!(
+        release_types,
+        [
+            ReleaseType::Ant,
+            ReleaseType::AntNode,
+            ReleaseType::NodeLaunchpad
+        ]
+    );
+    assert!(Platform::LinuxMusl < Platform::Windows);
+    assert!(ArchiveType::TarGz < ArchiveType::Zip);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn should_serialize_enums_as_their_display_strings() {
+    let json = serde_json::to_string(&(
+        ReleaseType::AntNodeRpcClient,
+        Platform::MacOsAarch64,
+        ArchiveType::TarGz,
+    ))
+    .unwrap();
+    assert_eq!(
+        json,
+        r#"["antnode_rpc_client","aarch64-apple-darwin","tar.gz"]"#
+    );
+
+    let parsed: (ReleaseType, Platform, ArchiveType) = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        parsed,
+        (
+            ReleaseType::AntNodeRpcClient,
+            Platform::MacOsAarch64,
+            ArchiveType::TarGz
+        )
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn should_deserialize_release_type_from_crate_name() {
+    let release_type: ReleaseType = serde_json::from_str(r#""ant-node-manager""#).unwrap();
+    assert_eq!(release_type, ReleaseType::AntCtl);
+
+    let err = serde_json::from_str::<ReleaseType>(r#""safenode""#).unwrap_err();
+    assert!(err.to_string().contains("safenode is not a release type"));
+}
@@ -0,0 +1,187 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{AntReleaseRepoActions, AntReleaseRepository, ProgressEvent};
+use assert_fs::prelude::*;
+use common::{download_antnode, etag, serve_bytes, MockServer, Response, ARCHIVE_NAME};
+
+fn archive_bytes() -> Vec<u8> {
+    (0..64 * 1024).map(|i| (i % 251) as u8).collect()
+}
+
+async fn start_server(supports_ranges: bool) -> MockServer {
+    let body = archive_bytes();
+    MockServer::start(move |request| {
+        if request.path == format!("/{ARCHIVE_NAME}") {
+            serve_bytes(request, &body, supports_ranges)
+        } else {
+            Response::not_found()
+        }
+    })
+    .await
+}
+
+/// Leaves a partial download behind, as an interrupted attempt would, along with the validator it
+/// was started with.
+fn write_partial_download(dest_dir: &assert_fs::TempDir, contents: &[u8], validator: &str) {
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part"))
+        .write_binary(contents)
+        .unwrap();
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part.validator"))
+        .write_str(validator)
+        .unwrap();
+}
+
+fn read_archive(dest_dir: &assert_fs::TempDir) -> Vec<u8> {
+    std::fs::read(dest_dir.child(ARCHIVE_NAME).path()).unwrap()
+}
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        antnode_base_url: server.url(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_download_to_part_file_and_rename_on_completion() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), archive_bytes());
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part"))
+        .assert(predicates::path::missing());
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part.validator"))
+        .assert(predicates::path::missing());
+    assert!(server.requests_for(&format!("/{ARCHIVE_NAME}"))[0]
+        .header("range")
+        .is_none());
+}
+
+#[tokio::test]
+async fn should_resume_partial_download_when_server_supports_ranges() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let bytes = archive_bytes();
+    write_partial_download(&dest_dir, &bytes[..1000], &etag(&bytes));
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), bytes);
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].header("range"), Some("bytes=1000-"));
+    assert_eq!(requests[0].header("if-range"), Some(etag(&bytes).as_str()));
+}
+
+#[tokio::test]
+async fn should_restart_download_when_server_ignores_ranges() {
+    let server = start_server(false).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    write_partial_download(
+        &dest_dir,
+        b"garbage that is not a prefix of the archive",
+        &etag(&archive_bytes()),
+    );
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), archive_bytes());
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].header("range").is_some());
+}
+
+#[tokio::test]
+async fn should_restart_download_when_range_is_not_satisfiable() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let mut oversized = archive_bytes();
+    oversized.extend_from_slice(b"trailing bytes");
+    write_partial_download(&dest_dir, &oversized, &etag(&archive_bytes()));
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), archive_bytes());
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].header("range").is_none());
+}
+
+#[tokio::test]
+async fn should_resume_partial_download_of_custom_url() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let bytes = archive_bytes();
+    write_partial_download(&dest_dir, &bytes[..10], &etag(&bytes));
+
+    let release_repo = AntReleaseRepository::default();
+    let progress_callback = |_event: ProgressEvent| {};
+    release_repo
+        .download_release(
+            &format!("{}/{}", server.url(), ARCHIVE_NAME),
+            &dest_dir,
+            &progress_callback,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), bytes);
+    assert_eq!(server.requests()[0].header("range"), Some("bytes=10-"));
+}
+
+#[tokio::test]
+async fn should_restart_download_when_partial_file_has_no_validator() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    dest_dir
+        .child(format!("{ARCHIVE_NAME}.part"))
+        .write_binary(b"bytes of unknown origin")
+        .unwrap();
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), archive_bytes());
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests.len(), 1);
+    assert!(requests[0].header("range").is_none());
+}
+
+#[tokio::test]
+async fn should_restart_download_when_archive_changed_since_partial_download() {
+    let server = start_server(true).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    write_partial_download(&dest_dir, b"the start of an older archive", "\"older\"");
+
+    download_antnode(&repo_for(&server), &dest_dir)
+        .await
+        .unwrap();
+
+    assert_eq!(read_archive(&dest_dir), archive_bytes());
+    let requests = server.requests_for(&format!("/{ARCHIVE_NAME}"));
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].header("if-range"), Some("\"older\""));
+}
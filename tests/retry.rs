@@ -0,0 +1,177 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ProgressEvent, RetryPolicy,
+};
+use common::{etag, serve_bytes, MockServer, Response, ARCHIVE_NAME};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn fast_retry_policy(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_attempts,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(5),
+        ..Default::default()
+    }
+}
+
+/// Starts a server that fails the first `failures` requests with the given response.
+async fn start_flaky_server(failures: usize, failure: Response) -> MockServer {
+    let body = b"archive contents".to_vec();
+    let count = AtomicUsize::new(0);
+    MockServer::start(move |request| {
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            failure.clone()
+        } else {
+            serve_bytes(request, &body, true)
+        }
+    })
+    .await
+}
+
+async fn download(release_repo: &AntReleaseRepository, server: &MockServer) -> Result<(), Error> {
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+    let progress_callback = |_event: ProgressEvent| {};
+    release_repo
+        .download_release(
+            &format!("{}/{}", server.url(), ARCHIVE_NAME),
+            &dest_dir,
+            &progress_callback,
+        )
+        .await?;
+    assert_eq!(
+        std::fs::read(dest_dir.path().join(ARCHIVE_NAME)).unwrap(),
+        b"archive contents"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn should_retry_on_service_unavailable() {
+    let server = start_flaky_server(2, Response::new(503, "Slow Down")).await;
+    let retries = Arc::new(Mutex::new(Vec::new()));
+    let retries_clone = retries.clone();
+    let release_repo = AntReleaseRepository {
+        retry_policy: fast_retry_policy(3),
+        on_retry: Some(Arc::new(move |attempt, err| {
+            retries_clone
+                .lock()
+                .unwrap()
+                .push((attempt, err.to_string()));
+        })),
+        ..Default::default()
+    };
+
+    download(&release_repo, &server).await.unwrap();
+
+    assert_eq!(server.requests().len(), 3);
+    let retries = retries.lock().unwrap();
+    assert_eq!(retries.len(), 2);
+    assert_eq!(retries[0].0, 2);
+    assert_eq!(retries[1].0, 3);
+    assert!(retries[0].1.contains("503"));
+}
+
+#[tokio::test]
+async fn should_fail_after_max_attempts_are_exhausted() {
+    let server = start_flaky_server(usize::MAX, Response::new(503, "Slow Down")).await;
+    let release_repo = AntReleaseRepository {
+        retry_policy: fast_retry_policy(3),
+        ..Default::default()
+    };
+
+    let result = download(&release_repo, &server).await;
+
+    match result {
+        Err(Error::UnexpectedResponseStatus(status, _)) => assert_eq!(status, 503),
+        other => panic!("Expected UnexpectedResponseStatus error, got {other:?}"),
+    }
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn should_not_retry_when_release_binary_is_not_found() {
+    let server = start_flaky_server(usize::MAX, Response::not_found()).await;
+    let release_repo = AntReleaseRepository {
+        retry_policy: fast_retry_policy(5),
+        ..Default::default()
+    };
+
+    let result = download(&release_repo, &server).await;
+
+    assert!(matches!(result, Err(Error::ReleaseBinaryNotFound(_))));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn should_not_retry_when_retries_are_disabled() {
+    let server = start_flaky_server(1, Response::new(500, "Internal Error")).await;
+    let release_repo = AntReleaseRepository {
+        retry_policy: RetryPolicy::no_retries(),
+        ..Default::default()
+    };
+
+    let result = download(&release_repo, &server).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::UnexpectedResponseStatus(500, _))
+    ));
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn should_resume_from_where_interrupted_attempt_stopped() {
+    let server = start_flaky_server(
+        1,
+        Response::new(200, b"archive contents".to_vec())
+            .with_header("ETag", &etag(b"archive contents"))
+            .truncated(7),
+    )
+    .await;
+    let release_repo = AntReleaseRepository {
+        retry_policy: fast_retry_policy(2),
+        ..Default::default()
+    };
+
+    download(&release_repo, &server).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].header("range"), Some("bytes=7-"));
+}
+
+#[test]
+fn should_cap_exponential_backoff_at_max_delay() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(1000),
+        jitter: false,
+        ..Default::default()
+    };
+    assert_eq!(policy.delay_for_attempt(1), Duration::from_millis(100));
+    assert_eq!(policy.delay_for_attempt(2), Duration::from_millis(200));
+    assert_eq!(policy.delay_for_attempt(4), Duration::from_millis(800));
+    assert_eq!(policy.delay_for_attempt(5), Duration::from_millis(1000));
+    assert_eq!(policy.delay_for_attempt(40), Duration::from_millis(1000));
+
+    let jittered = RetryPolicy {
+        jitter: true,
+        ..policy
+    };
+    for attempt in 1..10 {
+        let delay = jittered.delay_for_attempt(attempt);
+        assert!(delay <= Duration::from_millis(1000));
+        assert!(delay >= Duration::from_millis(50));
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{
+    error::Error, AntReleaseRepoActions, AntReleaseRepository, ArchiveType, Platform, ReleaseType,
+    VersionSource,
+};
+use chrono::{TimeZone, Utc};
+use common::{list_objects_xml, MockServer, Response};
+use semver::Version;
+use std::collections::HashMap;
+
+const CONTINUATION_TOKEN: &str = "1ueGcxLPRx1Tr/XYExHnhbYLgveDs2J/wm36Hy4vbOwM=";
+
+/// Starts a stand-in for the node launchpad bucket, whose listing is split over two pages.
+async fn start_launchpad_bucket() -> MockServer {
+    MockServer::start(|request| {
+        if request.path_only() != "/" || request.query_param("list-type").as_deref() != Some("2") {
+            return Response::not_found();
+        }
+        assert_eq!(
+            request.query_param("prefix").as_deref(),
+            Some("node-launchpad-")
+        );
+        match request.query_param("continuation-token").as_deref() {
+            None => Response::new(
+                200,
+                list_objects_xml(
+                    &[
+                        (
+                            "node-launchpad-0.4.5-x86_64-unknown-linux-musl.tar.gz",
+                            1000,
+                            "2024-11-01T10:00:00.000Z",
+                        ),
+                        (
+                            "node-launchpad-0.4.5-x86_64-pc-windows-msvc.zip",
+                            1000,
+                            "2024-11-01T09:00:00.000Z",
+                        ),
+                        (
+                            "node-launchpad-0.4.6-rc.1-x86_64-unknown-linux-musl.tar.gz",
+                            1000,
+                            "2024-12-05T10:00:00.000Z",
+                        ),
+                    ],
+                    Some(CONTINUATION_TOKEN),
+                ),
+            ),
+            Some(CONTINUATION_TOKEN) => Response::new(
+                200,
+                list_objects_xml(
+                    &[
+                        (
+                            "node-launchpad-0.4.6-aarch64-apple-darwin.tar.gz",
+                            1000,
+                            "2024-12-10T10:00:00.000Z",
+                        ),
+                        ("node-launchpad-latest.txt", 10, "2024-12-10T10:00:00.000Z"),
+                    ],
+                    None,
+                ),
+            ),
+            Some(_) => Response::new(400, "Invalid continuation token"),
+        }
+    })
+    .await
+}
+
+fn repo_for(server: &MockServer) -> AntReleaseRepository {
+    AntReleaseRepository {
+        node_launchpad_base_url: server.url(),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn should_get_latest_node_launchpad_version_from_bucket_listing() {
+    let server = start_launchpad_bucket().await;
+    let release_repo = repo_for(&server);
+
+    let version = release_repo
+        .get_latest_version(&ReleaseType::NodeLaunchpad)
+        .await
+        .unwrap();
+
+    assert_eq!(version, Version::parse("0.4.6").unwrap());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn should_list_node_launchpad_versions_from_bucket_listing() {
+    let server = start_launchpad_bucket().await;
+    let release_repo = repo_for(&server);
+
+    let releases = release_repo
+        .list_versions(&ReleaseType::NodeLaunchpad)
+        .await
+        .unwrap();
+
+    let versions: Vec<String> = releases.iter().map(|r| r.version.to_string()).collect();
+    assert_eq!(versions, ["0.4.6", "0.4.6-rc.1", "0.4.5"]);
+    // The earliest upload of each version is taken as its publication date.
+    assert_eq!(
+        releases[2].published_at,
+        Utc.with_ymd_and_hms(2024, 11, 1, 9, 0, 0).unwrap()
+    );
+    assert!(releases.iter().all(|r| !r.yanked));
+}
+
+#[tokio::test]
+async fn should_use_bucket_listing_for_release_type_configured_to_use_it() {
+    let server = MockServer::start(|_| {
+        Response::new(
+            200,
+            list_objects_xml(
+                &[
+                    (
+                        "antnode-0.112.6-x86_64-unknown-linux-musl.tar.gz",
+                        1000,
+                        "2024-12-01T10:00:00Z",
+                    ),
+                    (
+                        "antnode-0.112.7-x86_64-unknown-linux-musl.tar.gz",
+                        1000,
+                        "2024-12-12T10:00:00Z",
+                    ),
+                ],
+                None,
+            ),
+        )
+    })
+    .await;
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        crates_io_api_base_url: "http://127.0.0.1:1".to_string(),
+        version_sources: HashMap::from([(ReleaseType::AntNode, VersionSource::S3Listing)]),
+        ..Default::default()
+    };
+
+    let version = release_repo
+        .get_latest_version(&ReleaseType::AntNode)
+        .await
+        .unwrap();
+
+    assert_eq!(version, Version::parse("0.112.7").unwrap());
+    assert_eq!(
+        server.requests()[0].query_param("prefix").as_deref(),
+        Some("antnode-")
+    );
+}
+
+#[tokio::test]
+async fn should_fail_when_bucket_has_no_stable_release() {
+    let server = MockServer::start(|_| {
+        Response::new(
+            200,
+            list_objects_xml(
+                &[(
+                    "node-launchpad-0.4.6-rc.1-x86_64-unknown-linux-musl.tar.gz",
+                    1000,
+                    "2024-12-05T10:00:00Z",
+                )],
+                None,
+            ),
+        )
+    })
+    .await;
+    let release_repo = repo_for(&server);
+
+    let result = release_repo
+        .get_latest_version(&ReleaseType::NodeLaunchpad)
+        .await;
+
+    assert!(matches!(result, Err(Error::LatestReleaseNotFound(_))));
+}
+
+#[tokio::test]
+async fn should_fail_on_malformed_bucket_listing() {
+    let server = MockServer::start(|_| {
+        Response::new(
+            200,
+            "<ListBucketResult><Contents><Key>a</Key></Contents></ListBucketResult>",
+        )
+    })
+    .await;
+    let release_repo = repo_for(&server);
+
+    let result = release_repo
+        .list_versions(&ReleaseType::NodeLaunchpad)
+        .await;
+
+    assert!(matches!(result, Err(Error::S3ListingParseError(_))));
+}
+
+#[tokio::test]
+async fn should_index_archives_in_bucket() {
+    let server = start_launchpad_bucket().await;
+    let release_repo = repo_for(&server);
+
+    let index = release_repo
+        .get_s3_release_index(&ReleaseType::NodeLaunchpad)
+        .await
+        .unwrap();
+
+    assert_eq!(index.entries().len(), 4);
+    assert_eq!(
+        index.versions(),
+        [
+            Version::parse("0.4.6").unwrap(),
+            Version::parse("0.4.6-rc.1").unwrap(),
+            Version::parse("0.4.5").unwrap()
+        ]
+    );
+    let version = Version::parse("0.4.5").unwrap();
+    assert_eq!(
+        index.platforms(&version),
+        [Platform::Windows, Platform::LinuxMusl]
+    );
+
+    let entry = index
+        .find(&version, &Platform::Windows, &ArchiveType::Zip)
+        .unwrap();
+    assert_eq!(entry.key, "node-launchpad-0.4.5-x86_64-pc-windows-msvc.zip");
+    assert_eq!(entry.release_type, ReleaseType::NodeLaunchpad);
+    assert_eq!(entry.size, 1000);
+    assert_eq!(
+        entry.last_modified,
+        Utc.with_ymd_and_hms(2024, 11, 1, 9, 0, 0).unwrap()
+    );
+    assert!(index
+        .find(&version, &Platform::MacOsAarch64, &ArchiveType::TarGz)
+        .is_none());
+}
+
+#[tokio::test]
+async fn should_leave_other_release_types_out_of_index() {
+    // The node manager daemon is uploaded to the same bucket as the node manager.
+    let server = MockServer::start(|_| {
+        Response::new(
+            200,
+            list_objects_xml(
+                &[
+                    (
+                        "antctl-0.11.3-x86_64-unknown-linux-musl.tar.gz",
+                        2048,
+                        "2024-12-01T10:00:00Z",
+                    ),
+                    (
+                        "antctld-0.11.3-x86_64-unknown-linux-musl.tar.gz",
+                        4096,
+                        "2024-12-01T10:00:00Z",
+                    ),
+                    (
+                        "antctl-0.11.3-x86_64-unknown-linux-musl.tar.gz.sha256",
+                        64,
+                        "2024-12-01T10:00:00Z",
+                    ),
+                ],
+                None,
+            ),
+        )
+    })
+    .await;
+    let release_repo = AntReleaseRepository {
+        antctl_base_url: server.url(),
+        ..Default::default()
+    };
+
+    let index = release_repo
+        .get_s3_release_index(&ReleaseType::AntCtl)
+        .await
+        .unwrap();
+
+    assert_eq!(index.entries().len(), 1);
+    assert_eq!(index.entries()[0].size, 2048);
+    assert_eq!(index.entries()[0].release_type, ReleaseType::AntCtl);
+}
@@ -0,0 +1,260 @@
+// Copyright (C) 2024 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+mod common;
+
+use ant_releases::{error::Error, AntReleaseRepository, ReleaseType, SignatureVerification};
+use assert_fs::prelude::*;
+use common::{
+    download_antnode, start_archive_server, MockServer, TestKey, ARCHIVE_CONTENTS, ARCHIVE_NAME,
+};
+use std::collections::HashSet;
+
+/// Downloads the archive from `server`, verifying its signature as configured.
+async fn download_with_verification(
+    server: &MockServer,
+    signature_verification: SignatureVerification,
+    dest_dir: &assert_fs::TempDir,
+) -> Result<std::path::PathBuf, Error> {
+    let release_repo = AntReleaseRepository {
+        antnode_base_url: server.url(),
+        signature_verification,
+        ..Default::default()
+    };
+    download_antnode(&release_repo, dest_dir).await
+}
+
+#[tokio::test]
+async fn should_accept_archive_signed_by_a_trusted_key() {
+    let key = TestKey::new(1);
+    let other_key = TestKey::new(2);
+    let server = start_archive_server(None, Some(key.sign(ARCHIVE_NAME, ARCHIVE_CONTENTS))).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let archive_path = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![other_key.public_key(), key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(std::fs::read(archive_path).unwrap(), ARCHIVE_CONTENTS);
+}
+
+#[tokio::test]
+async fn should_accept_public_key_in_minisign_pub_format() {
+    let key = TestKey::new(1);
+    let server = start_archive_server(None, Some(key.sign(ARCHIVE_NAME, ARCHIVE_CONTENTS))).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![format!(
+                "untrusted comment: minisign public key\n{}\n",
+                key.public_key()
+            )],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn should_reject_archive_signed_by_an_untrusted_key() {
+    let key = TestKey::new(1);
+    let untrusted_key = TestKey::new(3);
+    let server = start_archive_server(
+        None,
+        Some(untrusted_key.sign(ARCHIVE_NAME, ARCHIVE_CONTENTS)),
+    )
+    .await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureInvalid(_))));
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn should_reject_archive_whose_contents_do_not_match_signature() {
+    let key = TestKey::new(1);
+    let server =
+        start_archive_server(None, Some(key.sign(ARCHIVE_NAME, b"a different archive"))).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureInvalid(_))));
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn should_reject_archive_signed_for_a_different_file() {
+    let key = TestKey::new(1);
+    // A valid signature by a trusted key, but for an older release, as a mirror replaying that
+    // release under the requested name would serve.
+    let signature = key.sign(
+        "antnode-0.112.6-x86_64-unknown-linux-musl.tar.gz",
+        ARCHIVE_CONTENTS,
+    );
+    let server = start_archive_server(None, Some(signature)).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureFileMismatch(_))));
+    dest_dir
+        .child(ARCHIVE_NAME)
+        .assert(predicates::path::missing());
+}
+
+#[tokio::test]
+async fn should_fail_when_signature_is_not_published() {
+    let key = TestKey::new(1);
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureMissing(_))));
+    assert!(server.requests_for(&format!("/{ARCHIVE_NAME}")).is_empty());
+}
+
+#[tokio::test]
+async fn should_fail_when_signature_is_only_optional_for_other_release_types() {
+    let key = TestKey::new(1);
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::from([ReleaseType::AntCtl]),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureMissing(_))));
+}
+
+#[tokio::test]
+async fn should_allow_unsigned_archive_when_optional_for_release_type() {
+    let key = TestKey::new(1);
+    let server = start_archive_server(None, None).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::from([ReleaseType::AntNode]),
+        },
+        &dest_dir,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn should_verify_published_signature_when_optional_for_release_type() {
+    let key = TestKey::new(1);
+    let server =
+        start_archive_server(None, Some(key.sign(ARCHIVE_NAME, b"a different archive"))).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec![key.public_key()],
+            optional_for: HashSet::from([ReleaseType::AntNode]),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::SignatureInvalid(_))));
+}
+
+#[tokio::test]
+async fn should_not_fetch_signature_when_verification_is_not_configured() {
+    let server = start_archive_server(None, Some("not a signature".to_string())).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    download_with_verification(&server, SignatureVerification::default(), &dest_dir)
+        .await
+        .unwrap();
+
+    assert!(server
+        .requests_for(&format!("/{ARCHIVE_NAME}.sig"))
+        .is_empty());
+}
+
+#[tokio::test]
+async fn should_fail_with_invalid_public_key() {
+    let key = TestKey::new(1);
+    let server = start_archive_server(None, Some(key.sign(ARCHIVE_NAME, ARCHIVE_CONTENTS))).await;
+    let dest_dir = assert_fs::TempDir::new().unwrap();
+
+    let result = download_with_verification(
+        &server,
+        SignatureVerification {
+            trusted_public_keys: vec!["not-a-key".to_string()],
+            optional_for: HashSet::new(),
+        },
+        &dest_dir,
+    )
+    .await;
+
+    assert!(matches!(result, Err(Error::InvalidPublicKey(_))));
+}
@@ -6,7 +6,7 @@
 // KIND, either express or implied. Please review the Licences for the specific language governing
 // permissions and limitations relating to use of the SAFE Network Software.
 
-use ant_releases::{AntReleaseRepoActions, ArchiveType, Platform, ReleaseType};
+use ant_releases::{AntReleaseRepoActions, ArchiveType, ByteProgress, Platform, ReleaseType};
 use assert_fs::prelude::*;
 use predicates::prelude::*;
 use semver::Version;
@@ -31,7 +31,7 @@ async fn download_and_extract(
     let extract_dir = dest_dir.child("extract_to");
     extract_dir.create_dir_all().unwrap();
 
-    let progress_callback = |_downloaded: u64, _total: u64| {};
+    let progress_callback = ByteProgress(|_downloaded: u64, _total: u64| {});
 
     let release_repo = <dyn AntReleaseRepoActions>::default_config();
     let archive_path = release_repo